@@ -9,7 +9,7 @@ fn main() {
     // Problem Data
     let men = vec!["A", "B", "C"];
     let women = vec!["D", "E", "F"];
-    let compatibility_score: HashMap<(&str, &str),f32> = vec![
+    let compatibility_score: HashMap<(&str, &str),f64> = vec![
         (("A", "D"), 50.0),
         (("A", "E"), 75.0),
         (("A", "F"), 75.0),
@@ -25,15 +25,10 @@ fn main() {
     let mut problem = LpProblem::new("Matchmaking", LpObjective::Maximize);
 
     // Define Variables
-    let vars: HashMap<(&str,&str), LpBinary> =
-        men.iter()
-            .flat_map(|&m| women.iter()
-            .map(move |&w| {
-                let key = (m,w);
-                let value = LpBinary::new(&format!("{}_{}", m,w));
-                (key, value)
-            }))
-            .collect();
+    let vars = LpVarDict::binary(
+        "match",
+        men.iter().flat_map(|&m| women.iter().map(move |&w| (m, w)))
+    );
 
     // Define Objective Function
     let obj_vec: Vec<LpExpression> = {
@@ -46,13 +41,13 @@ fn main() {
 
     // Define Constraints
     // - constraint 1: Each man must be assigned to exactly one woman
-    for &m in &men{
-        problem += sum(&women, |&w| vars.get(&(m,w)).unwrap() ).equal(1);
+    for (_, assignments) in vars.sum_by(|&(m, _)| m) {
+        problem += assignments.equal(1);
     }
 
     // - constraint 2: Each woman must be assigned to exactly one man
-    for &w in &women{
-        problem += sum(&men, |&m| vars.get(&(m,w)).unwrap() ).equal(1);
+    for (_, assignments) in vars.sum_by(|&(_, w)| w) {
+        problem += assignments.equal(1);
     }
 
     // Run Solver
@@ -63,10 +58,9 @@ fn main() {
     // (terminate if error, or assign status & variable values)
     assert!(result.is_ok(), result.unwrap_err());
     let solution = result.unwrap();
-    let mut obj_value = 0f32;
-    for (&(m, w), var) in &vars{
+    let mut obj_value = 0f64;
+    for (&(m, w), var_value) in &solution.get_dict(&vars) {
         let obj_coef = compatibility_score.get(&(m, w)).unwrap();
-        let var_value = solution.results.get(&var.name).unwrap();
 
         obj_value += obj_coef * var_value;
     }
@@ -0,0 +1,332 @@
+//! Expansion of the model into code building an `LpProblem`, checking that the expressions
+//! are linear and only use declared names.
+use std::collections::{HashMap, HashSet};
+
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{Error, Ident, Result};
+
+use crate::parse::{Comparison, ConstraintDecl, Domain, Model, Node, Op, Statement, VarDecl};
+
+/// Names usable in the expressions
+#[derive(Default)]
+struct Scope {
+    /// Variables with their number of indices
+    vars: HashMap<String, usize>,
+    params: HashSet<String>,
+    sets: HashSet<String>,
+    /// Indices bound by the enclosing sums and constraint families
+    indices: Vec<String>,
+}
+
+/// Code of an expression: a constant (`f64`) or a linear `LpExpression`
+struct Expansion {
+    code: TokenStream,
+    linear: bool,
+}
+
+impl Expansion {
+    fn constant(code: TokenStream) -> Expansion {
+        Expansion { code, linear: false }
+    }
+
+    fn linear(code: TokenStream) -> Expansion {
+        Expansion { code, linear: true }
+    }
+
+    /// Code of type `LpExpression`
+    fn to_expression(&self) -> TokenStream {
+        let code = &self.code;
+        if self.linear {
+            code.clone()
+        } else {
+            quote!(::lp_modeler::dsl::LpExpression::from(#code))
+        }
+    }
+}
+
+pub fn expand(model: Model) -> Result<TokenStream> {
+    let mut scope = Scope::default();
+    let problem = &model.problem.name;
+    let title = &model.problem.title;
+    let sense = if model.problem.sense == "minimize" {
+        quote!(::lp_modeler::dsl::LpObjective::Minimize)
+    } else {
+        quote!(::lp_modeler::dsl::LpObjective::Maximize)
+    };
+    let mut code = quote! {
+        let mut #problem = ::lp_modeler::dsl::LpProblem::new(#title, #sense);
+    };
+    for statement in &model.statements {
+        code.extend(match statement {
+            Statement::Set(name, expr) => {
+                scope.sets.insert(name.to_string());
+                quote! {
+                    let #name: ::std::vec::Vec<_> = ::std::iter::IntoIterator::into_iter(#expr).collect();
+                }
+            }
+            Statement::Params(names) => {
+                scope.params.extend(names.iter().map(|name| name.to_string()));
+                TokenStream::new()
+            }
+            Statement::Var(decl) => {
+                let code = expand_var(decl, &mut scope)?;
+                scope.vars.insert(decl.name.to_string(), decl.sets.len());
+                code
+            }
+            Statement::Objective(expr) => {
+                let expr = expand_node(expr, &mut scope)?.to_expression();
+                quote! {
+                    #problem += #expr;
+                }
+            }
+            Statement::Constraint(decl) => expand_constraint(decl, problem, &mut scope)?,
+        });
+    }
+    Ok(code)
+}
+
+fn expand_var(decl: &VarDecl, scope: &mut Scope) -> Result<TokenStream> {
+    let name = &decl.name;
+    let name_str = name.to_string();
+    let kind = decl.kind.to_string();
+    let var_type = match kind.as_str() {
+        "continuous" => quote!(::lp_modeler::dsl::LpContinuous),
+        "integer" => quote!(::lp_modeler::dsl::LpInteger),
+        "binary" => quote!(::lp_modeler::dsl::LpBinary),
+        "semi_continuous" => quote!(::lp_modeler::dsl::LpSemiContinuous),
+        _ => quote!(::lp_modeler::dsl::LpSemiInteger),
+    };
+    let mut bounds = TokenStream::new();
+    for (bound, method) in &[(&decl.lower_bound, "lower_bound"), (&decl.upper_bound, "upper_bound")] {
+        if let Some(bound) = bound {
+            if kind == "binary" {
+                return Err(Error::new(decl.kind.span(), "binaries cannot have bounds"));
+            }
+            let value = expand_constant(bound, scope, "bound")?;
+            let method = format_ident!("{}", method);
+            bounds.extend(quote! {
+                let __lp_var = ::lp_modeler::dsl::BoundableLp::#method(&__lp_var, #value);
+            });
+        }
+    }
+    if decl.sets.is_empty() {
+        return Ok(quote! {
+            let #name = {
+                let __lp_var = #var_type::new(#name_str);
+                #bounds
+                __lp_var
+            };
+        });
+    }
+    for set in &decl.sets {
+        check_set(set, scope)?;
+    }
+    let keys = if decl.sets.len() == 1 {
+        let set = &decl.sets[0];
+        quote!(#set.iter().cloned())
+    } else {
+        let elements: Vec<Ident> = (0..decl.sets.len()).map(|k| format_ident!("__lp_key_{}", k)).collect();
+        let mut push = quote! {
+            __lp_keys.push((#(::std::clone::Clone::clone(#elements)),*));
+        };
+        for (element, set) in elements.iter().zip(&decl.sets).rev() {
+            push = quote! {
+                for #element in #set.iter() {
+                    #push
+                }
+            };
+        }
+        quote! {{
+            let mut __lp_keys = ::std::vec::Vec::new();
+            #push
+            __lp_keys
+        }}
+    };
+    Ok(quote! {
+        let #name = ::lp_modeler::dsl::LpVarDict::new(#name_str, #keys, |__lp_name: &str| {
+            let __lp_var = #var_type::new(__lp_name);
+            #bounds
+            __lp_var
+        });
+    })
+}
+
+fn expand_constraint(decl: &ConstraintDecl, problem: &Ident, scope: &mut Scope) -> Result<TokenStream> {
+    let depth = scope.indices.len();
+    if let Some(domain) = &decl.domain {
+        bind(domain, scope)?;
+    }
+    let lhs = expand_node(&decl.lhs, scope)?;
+    let rhs = expand_node(&decl.rhs, scope)?;
+    scope.indices.truncate(depth);
+    if !lhs.linear && !rhs.linear {
+        return Err(Error::new(decl.name.span(), "the constraint has no variable"));
+    }
+    let (lhs, rhs) = (lhs.to_expression(), rhs.to_expression());
+    let op = match decl.op {
+        Comparison::Le => quote!(le),
+        Comparison::Ge => quote!(ge),
+        Comparison::Eq => quote!(equal),
+    };
+    let name = decl.name.to_string();
+    let name = match &decl.domain {
+        None => quote!(#name),
+        Some(domain) => {
+            let format = format!("{}{}", name, "_{}".repeat(domain.bindings.len()));
+            let indices = domain.bindings.iter().map(|(index, _)| index);
+            quote!(&format!(#format, #(#indices),*))
+        }
+    };
+    let constraint = quote! {
+        #problem += ::lp_modeler::dsl::LpOperations::#op(&#lhs, #rhs).with_name(#name);
+    };
+    Ok(match &decl.domain {
+        None => constraint,
+        Some(domain) => loops(domain, constraint),
+    })
+}
+
+/// Nested loops over the sets of `domain`, running `body` for the indices passing the filter
+fn loops(domain: &Domain, body: TokenStream) -> TokenStream {
+    let mut code = match &domain.filter {
+        Some(filter) => quote!(if #filter { #body }),
+        None => body,
+    };
+    for (index, set) in domain.bindings.iter().rev() {
+        code = quote! {
+            for #index in #set.iter().cloned() {
+                #code
+            }
+        };
+    }
+    code
+}
+
+/// Add the indices of `domain` to the scope
+fn bind(domain: &Domain, scope: &mut Scope) -> Result<()> {
+    for (index, set) in &domain.bindings {
+        check_set(set, scope)?;
+        scope.indices.push(index.to_string());
+    }
+    Ok(())
+}
+
+fn check_set(set: &Ident, scope: &Scope) -> Result<()> {
+    if scope.sets.contains(&set.to_string()) {
+        Ok(())
+    } else {
+        Err(Error::new(set.span(), format!("unknown set `{}`, declare it with `set`", set)))
+    }
+}
+
+fn expand_constant(node: &Node, scope: &mut Scope, what: &str) -> Result<TokenStream> {
+    let expansion = expand_node(node, scope)?;
+    if expansion.linear {
+        return Err(Error::new(Span::call_site(), format!("a {} cannot use variables", what)));
+    }
+    Ok(expansion.code)
+}
+
+fn expand_node(node: &Node, scope: &mut Scope) -> Result<Expansion> {
+    Ok(match node {
+        Node::Number(value) => Expansion::constant(Literal::f64_unsuffixed(*value).into_token_stream()),
+        Node::Block(code) => Expansion::constant(quote!(((#code) as f64))),
+        Node::Name(name, indices) => expand_name(name, indices, scope)?,
+        Node::Neg(node) => {
+            let Expansion { code, linear } = expand_node(node, scope)?;
+            if linear {
+                Expansion::linear(quote!((-1.0 * #code)))
+            } else {
+                Expansion::constant(quote!((-#code)))
+            }
+        }
+        Node::Binary(left, op, span, right) => {
+            let left = expand_node(left, scope)?;
+            let right = expand_node(right, scope)?;
+            expand_binary(left, *op, *span, right)?
+        }
+        Node::Sum(domain, node) => {
+            let depth = scope.indices.len();
+            bind(domain, scope)?;
+            let term = expand_node(node, scope)?;
+            scope.indices.truncate(depth);
+            let code = &term.code;
+            if term.linear {
+                let body = loops(domain, quote!(__lp_sum += #code;));
+                Expansion::linear(quote! {{
+                    let mut __lp_sum = ::lp_modeler::dsl::LpLinearExpression::new();
+                    #body
+                    ::lp_modeler::dsl::LpExpression::from(__lp_sum)
+                }})
+            } else {
+                let body = loops(domain, quote!(__lp_sum += #code;));
+                Expansion::constant(quote! {{
+                    let mut __lp_sum = 0.0;
+                    #body
+                    __lp_sum
+                }})
+            }
+        }
+    })
+}
+
+fn expand_name(name: &Ident, indices: &[Vec<syn::Expr>], scope: &Scope) -> Result<Expansion> {
+    let key = name.to_string();
+    if let Some(&arity) = scope.vars.get(&key) {
+        return match (arity, indices) {
+            (0, []) => Ok(Expansion::linear(quote!(::lp_modeler::dsl::LpExpression::from(&#name)))),
+            (_, [index]) if index.len() == arity => {
+                let key = if arity == 1 {
+                    let index = &index[0];
+                    quote!(&(#index))
+                } else {
+                    quote!(&(#(::std::clone::Clone::clone(&(#index))),*))
+                };
+                Ok(Expansion::linear(quote!(::lp_modeler::dsl::LpExpression::from(&#name[#key]))))
+            }
+            _ => Err(Error::new(name.span(), format!("variable `{}` takes {} indices, as `{}[i, j]`", name, arity, name))),
+        };
+    }
+    if scope.params.contains(&key) {
+        let mut code = quote!(#name);
+        for index in indices {
+            if index.len() != 1 {
+                return Err(Error::new(name.span(), format!("parameter `{}` is indexed as `{}[i][j]`", name, name)));
+            }
+            let index = &index[0];
+            code = quote!(#code[#index]);
+        }
+        return Ok(Expansion::constant(quote!((#code as f64))));
+    }
+    if scope.indices.contains(&key) && indices.is_empty() {
+        return Ok(Expansion::constant(quote!((#name as f64))));
+    }
+    Err(Error::new(name.span(), format!("unknown variable `{}`, declare it with `var` or `param`", name)))
+}
+
+fn expand_binary(left: Expansion, op: Op, span: Span, right: Expansion) -> Result<Expansion> {
+    let (l, r) = (&left.code, &right.code);
+    Ok(match op {
+        Op::Add | Op::Sub => {
+            let op = if let Op::Add = op { quote!(+) } else { quote!(-) };
+            if left.linear || right.linear {
+                let l = left.to_expression();
+                Expansion::linear(quote!((#l #op #r)))
+            } else {
+                Expansion::constant(quote!((#l #op #r)))
+            }
+        }
+        Op::Mul => match (left.linear, right.linear) {
+            (true, true) => return Err(Error::new(span, "nonlinear term: product of variables")),
+            (false, false) => Expansion::constant(quote!((#l * #r))),
+            (true, false) => Expansion::linear(quote!((#r * #l))),
+            (false, true) => Expansion::linear(quote!((#l * #r))),
+        },
+        Op::Div => match (left.linear, right.linear) {
+            (_, true) => return Err(Error::new(span, "nonlinear term: division by a variable")),
+            (false, false) => Expansion::constant(quote!((#l / #r))),
+            (true, false) => Expansion::linear(quote!(((1.0 / #r) * #l))),
+        },
+    })
+}
@@ -0,0 +1,87 @@
+//! Declarative model definitions for [lp-modeler](https://github.com/jcavat/rust-lp-modeler).
+//!
+//! The macro is also available as `lp_modeler::lp_model` with the `macros` feature of `lp-modeler`.
+extern crate proc_macro;
+
+mod expand;
+mod parse;
+
+use proc_macro::TokenStream;
+use syn::parse_macro_input;
+
+/// Declare a problem with its sets, variables, objective and constraints.
+///
+/// The macro expands to statements declaring the problem, the sets (as `Vec`s) and the
+/// variables (`LpVarDict`s for indexed variables) under the given names, so that they can
+/// be used afterwards, e.g. to read a solution.
+///
+/// * `problem name = minimize "Title";` (or `maximize`) comes first
+/// * `set name = expr;` collects any `IntoIterator` into a set of indices
+/// * `param a, b;` allows Rust values in scope (numbers, or indexed as `a[i][j]`) in the expressions
+/// * `var x[set, other]: kind >= lower <= upper;` with the kinds `continuous`, `integer`,
+///   `binary`, `semi_continuous` and `semi_integer`; indexed variables are named `x_{i}_{j}`
+/// * `objective: expr;`
+/// * `constraint name[i in set, j in other if condition]: lhs <= rhs;` (or `>=`, `==`)
+///   adds a constraint `name_{i}_{j}` for each combination of indices passing the condition
+///
+/// Expressions combine numbers, parameters, indices, variables `x[i, j]`, sums
+/// `sum(i in set, j in other if condition: expr)` and Rust expressions in braces `{ .. }`
+/// with `+`, `-`, `*` and `/`.
+///
+/// # Examples
+///
+/// ```
+/// use lp_modeler::dsl::*;
+/// use lp_modeler_macros::lp_model;
+/// use lp_modeler::solvers::{MiniLpSolver, SolverTrait};
+///
+/// let capacity = vec![30.0, 25.0];
+/// let demand = vec![10.0, 20.0, 15.0];
+/// let cost = vec![vec![4.0, 6.0, 9.0], vec![5.0, 3.0, 7.0]];
+///
+/// lp_model! {
+///     problem transport = minimize "Transport";
+///     param capacity, demand, cost;
+///     set plants = 0..2;
+///     set markets = 0..3;
+///     var ship[plants, markets]: continuous >= 0;
+///     var open[plants]: binary;
+///     objective: sum(p in plants, m in markets: cost[p][m] * ship[p, m]) + sum(p in plants: 10 * open[p]);
+///     constraint supply[p in plants]: sum(m in markets: ship[p, m]) <= capacity[p] * open[p];
+///     constraint serve[m in markets]: sum(p in plants: ship[p, m]) >= demand[m];
+/// }
+///
+/// let solution = MiniLpSolver::new().run(&transport).unwrap();
+/// assert_eq!(solution.get_bool(&open[&1]), true);
+/// ```
+///
+/// Products of variables and unknown names are rejected at compile time:
+///
+/// ```compile_fail
+/// use lp_modeler_macros::lp_model;
+///
+/// lp_model! {
+///     problem p = maximize "Nonlinear";
+///     var x: continuous;
+///     var y: continuous;
+///     objective: x * y;
+/// }
+/// ```
+///
+/// ```compile_fail
+/// use lp_modeler_macros::lp_model;
+///
+/// lp_model! {
+///     problem p = maximize "Unknown";
+///     var x: continuous <= 10;
+///     objective: x + z;
+/// }
+/// ```
+#[proc_macro]
+pub fn lp_model(input: TokenStream) -> TokenStream {
+    let model = parse_macro_input!(input as parse::Model);
+    match expand::expand(model) {
+        Ok(code) => code.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
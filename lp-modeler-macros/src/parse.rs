@@ -0,0 +1,291 @@
+//! Syntax of the model: statements and linear expressions.
+use proc_macro2::{Span, TokenStream};
+use syn::parse::{Parse, ParseStream, Result};
+use syn::punctuated::Punctuated;
+use syn::{braced, bracketed, parenthesized, Expr, Ident, Lit, LitStr, Token};
+
+mod kw {
+    syn::custom_keyword!(problem);
+    syn::custom_keyword!(set);
+    syn::custom_keyword!(param);
+    syn::custom_keyword!(var);
+    syn::custom_keyword!(objective);
+    syn::custom_keyword!(constraint);
+    syn::custom_keyword!(sum);
+}
+
+pub struct Model {
+    pub problem: ProblemDecl,
+    pub statements: Vec<Statement>,
+}
+
+pub struct ProblemDecl {
+    pub name: Ident,
+    pub sense: Ident,
+    pub title: LitStr,
+}
+
+pub enum Statement {
+    Set(Ident, Expr),
+    Params(Vec<Ident>),
+    Var(VarDecl),
+    Objective(Node),
+    Constraint(ConstraintDecl),
+}
+
+pub struct VarDecl {
+    pub name: Ident,
+    pub sets: Vec<Ident>,
+    pub kind: Ident,
+    pub lower_bound: Option<Node>,
+    pub upper_bound: Option<Node>,
+}
+
+pub struct ConstraintDecl {
+    pub name: Ident,
+    pub domain: Option<Domain>,
+    pub lhs: Node,
+    pub op: Comparison,
+    pub rhs: Node,
+}
+
+#[derive(Clone, Copy)]
+pub enum Comparison {
+    Le,
+    Ge,
+    Eq,
+}
+
+/// `i in set, j in other if condition`
+pub struct Domain {
+    pub bindings: Vec<(Ident, Ident)>,
+    pub filter: Option<Expr>,
+}
+
+#[derive(Clone, Copy)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+pub enum Node {
+    Number(f64),
+    /// Rust expression in braces, used as a constant
+    Block(TokenStream),
+    /// Variable, parameter or index, with the expressions of its brackets
+    Name(Ident, Vec<Vec<Expr>>),
+    Neg(Box<Node>),
+    Binary(Box<Node>, Op, Span, Box<Node>),
+    Sum(Domain, Box<Node>),
+}
+
+impl Parse for Model {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if !input.peek(kw::problem) {
+            return Err(input.error("expected `problem name = minimize \"Title\";` first"));
+        }
+        input.parse::<kw::problem>()?;
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let sense: Ident = input.parse()?;
+        if sense != "minimize" && sense != "maximize" {
+            return Err(syn::Error::new(sense.span(), "expected `minimize` or `maximize`"));
+        }
+        let title = input.parse()?;
+        input.parse::<Token![;]>()?;
+
+        let mut statements = Vec::new();
+        while !input.is_empty() {
+            statements.push(input.parse()?);
+        }
+        Ok(Model { problem: ProblemDecl { name, sense, title }, statements })
+    }
+}
+
+impl Parse for Statement {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        let statement = if lookahead.peek(kw::set) {
+            input.parse::<kw::set>()?;
+            let name = input.parse()?;
+            input.parse::<Token![=]>()?;
+            Statement::Set(name, input.parse()?)
+        } else if lookahead.peek(kw::param) {
+            input.parse::<kw::param>()?;
+            let names = Punctuated::<Ident, Token![,]>::parse_separated_nonempty(input)?;
+            Statement::Params(names.into_iter().collect())
+        } else if lookahead.peek(kw::var) {
+            input.parse::<kw::var>()?;
+            Statement::Var(input.parse()?)
+        } else if lookahead.peek(kw::objective) {
+            input.parse::<kw::objective>()?;
+            input.parse::<Token![:]>()?;
+            Statement::Objective(input.parse()?)
+        } else if lookahead.peek(kw::constraint) {
+            input.parse::<kw::constraint>()?;
+            Statement::Constraint(input.parse()?)
+        } else {
+            return Err(lookahead.error());
+        };
+        input.parse::<Token![;]>()?;
+        Ok(statement)
+    }
+}
+
+impl Parse for VarDecl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse()?;
+        let mut sets = Vec::new();
+        if input.peek(syn::token::Bracket) {
+            let content;
+            bracketed!(content in input);
+            sets = Punctuated::<Ident, Token![,]>::parse_separated_nonempty(&content)?
+                .into_iter()
+                .collect();
+        }
+        input.parse::<Token![:]>()?;
+        let kind: Ident = input.parse()?;
+        let kinds = ["continuous", "integer", "binary", "semi_continuous", "semi_integer"];
+        if !kinds.iter().any(|k| kind == k) {
+            return Err(syn::Error::new(kind.span(), format!("expected one of {}", kinds.join(", "))));
+        }
+        let mut lower_bound = None;
+        let mut upper_bound = None;
+        loop {
+            if input.peek(Token![>=]) {
+                input.parse::<Token![>=]>()?;
+                lower_bound = Some(input.parse()?);
+            } else if input.peek(Token![<=]) {
+                input.parse::<Token![<=]>()?;
+                upper_bound = Some(input.parse()?);
+            } else {
+                break;
+            }
+        }
+        Ok(VarDecl { name, sets, kind, lower_bound, upper_bound })
+    }
+}
+
+impl Parse for ConstraintDecl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = input.parse()?;
+        let mut domain = None;
+        if input.peek(syn::token::Bracket) {
+            let content;
+            bracketed!(content in input);
+            domain = Some(content.parse()?);
+        }
+        input.parse::<Token![:]>()?;
+        let lhs = input.parse()?;
+        let lookahead = input.lookahead1();
+        let op = if lookahead.peek(Token![<=]) {
+            input.parse::<Token![<=]>()?;
+            Comparison::Le
+        } else if lookahead.peek(Token![>=]) {
+            input.parse::<Token![>=]>()?;
+            Comparison::Ge
+        } else if lookahead.peek(Token![==]) {
+            input.parse::<Token![==]>()?;
+            Comparison::Eq
+        } else {
+            return Err(lookahead.error());
+        };
+        let rhs = input.parse()?;
+        Ok(ConstraintDecl { name, domain, lhs, op, rhs })
+    }
+}
+
+impl Parse for Domain {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut bindings = Vec::new();
+        loop {
+            let index = input.parse()?;
+            input.parse::<Token![in]>()?;
+            bindings.push((index, input.parse()?));
+            if !input.peek(Token![,]) {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        let mut filter = None;
+        if input.peek(Token![if]) {
+            input.parse::<Token![if]>()?;
+            filter = Some(input.parse()?);
+        }
+        Ok(Domain { bindings, filter })
+    }
+}
+
+impl Parse for Node {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut node = parse_term(input)?;
+        loop {
+            let (op, span) = if input.peek(Token![+]) {
+                (Op::Add, input.parse::<Token![+]>()?.span)
+            } else if input.peek(Token![-]) {
+                (Op::Sub, input.parse::<Token![-]>()?.span)
+            } else {
+                return Ok(node);
+            };
+            node = Node::Binary(Box::new(node), op, span, Box::new(parse_term(input)?));
+        }
+    }
+}
+
+fn parse_term(input: ParseStream) -> Result<Node> {
+    let mut node = parse_factor(input)?;
+    loop {
+        let (op, span) = if input.peek(Token![*]) {
+            (Op::Mul, input.parse::<Token![*]>()?.span)
+        } else if input.peek(Token![/]) {
+            (Op::Div, input.parse::<Token![/]>()?.span)
+        } else {
+            return Ok(node);
+        };
+        node = Node::Binary(Box::new(node), op, span, Box::new(parse_factor(input)?));
+    }
+}
+
+fn parse_factor(input: ParseStream) -> Result<Node> {
+    if input.peek(Token![-]) {
+        input.parse::<Token![-]>()?;
+        return Ok(Node::Neg(Box::new(parse_factor(input)?)));
+    }
+    if input.peek(syn::token::Paren) {
+        let content;
+        parenthesized!(content in input);
+        return content.parse();
+    }
+    if input.peek(syn::token::Brace) {
+        let content;
+        braced!(content in input);
+        return Ok(Node::Block(content.parse()?));
+    }
+    if input.peek(Lit) {
+        return match input.parse()? {
+            Lit::Int(lit) => Ok(Node::Number(lit.base10_parse()?)),
+            Lit::Float(lit) => Ok(Node::Number(lit.base10_parse()?)),
+            lit => Err(syn::Error::new(lit.span(), "expected a number")),
+        };
+    }
+    if input.peek(kw::sum) && input.peek2(syn::token::Paren) {
+        input.parse::<kw::sum>()?;
+        let content;
+        parenthesized!(content in input);
+        let domain = content.parse()?;
+        content.parse::<Token![:]>()?;
+        return Ok(Node::Sum(domain, Box::new(content.parse()?)));
+    }
+    let name = input.parse()?;
+    let mut indices = Vec::new();
+    while input.peek(syn::token::Bracket) {
+        let content;
+        bracketed!(content in input);
+        indices.push(Punctuated::<Expr, Token![,]>::parse_separated_nonempty(&content)?
+            .into_iter()
+            .collect());
+    }
+    Ok(Node::Name(name, indices))
+}
@@ -0,0 +1,60 @@
+use lp_modeler::dsl::*;
+use lp_modeler::format::lp_format::LpFileFormat;
+use lp_modeler::solvers::{MiniLpSolver, SolverTrait, Status};
+use lp_modeler_macros::lp_model;
+
+#[test]
+fn test_scalar_model() {
+    lp_model! {
+        problem problem = maximize "Scalar";
+        var a: integer >= 0 <= 10;
+        var b: continuous >= -1;
+        objective: 2 * a + b / 2 - 3;
+        constraint capacity: a + b <= 12.5;
+        constraint gap: a - (b - 1) * 2 >= -{ 2 + 2 };
+    }
+    assert_eq!(a, LpInteger::new("a").lower_bound(0).upper_bound(10));
+
+    let output = problem.to_lp_file_format();
+    assert!(output.contains("Maximize\n  obj: 2 a + 0.5 b\n"));
+    assert!(output.contains("  capacity: a + b <= 12.5\n"));
+    assert!(output.contains("  gap: a - 2 b >= -6\n"));
+    assert!(output.contains("  0 <= a <= 10\n"));
+
+    let solution = MiniLpSolver::new().run(&problem).expect("could not solve");
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!(solution.get_int(&a), 10);
+}
+
+#[test]
+fn test_indexed_model() {
+    let capacity = vec![30.0, 25.0];
+    let demand = vec![10, 20, 15];
+    let cost = vec![vec![4.0, 6.0, 9.0], vec![5.0, 3.0, 7.0]];
+
+    lp_model! {
+        problem transport = minimize "Transport";
+        param capacity, demand, cost;
+        set plants = 0..2;
+        set markets = 0..3;
+        var ship[plants, markets]: continuous >= 0;
+        var open[plants]: binary;
+        objective: sum(p in plants, m in markets: cost[p][m] * ship[p, m]) + sum(p in plants: 10 * open[p]);
+        constraint supply[p in plants]: sum(m in markets: ship[p, m]) <= capacity[p] * open[p];
+        constraint serve[m in markets]: sum(p in plants: ship[p, m]) >= demand[m];
+        constraint single[p in plants, m in markets if m > p]: ship[p, m] <= sum(q in markets if q != m: demand[q]);
+    }
+    assert_eq!(plants, vec![0, 1]);
+    assert_eq!(ship.keys().len(), 6);
+
+    let output = transport.to_lp_file_format();
+    assert!(output.contains("  supply_1: ship_1_0 + ship_1_1 + ship_1_2 - 25 open_1 <= 0\n"));
+    assert!(output.contains("  serve_2: ship_0_2 + ship_1_2 >= 15\n"));
+    assert!(output.contains("  single_0_2: ship_0_2 <= 30\n"));
+    assert!(!output.contains("single_1_0"));
+
+    let solution = MiniLpSolver::new().run(&transport).expect("could not solve");
+    assert_eq!(solution.status, Status::Optimal);
+    assert!(solution.get_bool(&open[&1]));
+    assert!(solution.get_float(&ship[&(1, 1)]) >= 20.0 - 1e-6);
+}
@@ -0,0 +1,258 @@
+//! Families of variables sharing a type and bounds, indexed by a key.
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::ops::Index;
+
+use dsl::{lp_sum, BoundableLp, LpBinary, LpContinuous, LpExpression, LpInteger, SummableExp};
+
+/// Key of a variable dictionary. The key is turned into a suffix of the variable names:
+/// `x` indexed by `(1, "a")` is named `x_1_a`.
+///
+/// It is implemented for the integers, `char`, `bool`, strings and tuples of up to four
+/// displayable elements. A type of the user implements it by hand, or with
+/// `display_index_key!` to use its `Display` as the suffix.
+///
+/// # Examples
+///
+/// ```
+/// use std::fmt;
+/// use lp_modeler::dsl::*;
+/// use lp_modeler::display_index_key;
+///
+/// #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// struct Arc(u32, u32);
+///
+/// impl fmt::Display for Arc {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         write!(f, "{}_{}", self.0, self.1)
+///     }
+/// }
+/// display_index_key!(Arc);
+///
+/// let ref flow = LpVarDict::continuous("flow", vec![Arc(1, 2), Arc(2, 3)]);
+/// assert_eq!(flow[&Arc(1, 2)].name, "flow_1_2");
+/// ```
+pub trait IndexKey: Hash + Eq + Clone {
+    fn name_suffix(&self) -> String;
+}
+
+/// Implement `IndexKey` for types implementing `Display`, `Hash`, `Eq` and `Clone`, with
+/// their `Display` as the suffix of the variable names.
+#[macro_export]
+macro_rules! display_index_key {
+    ($($key_type: ty),*) => {
+        $(
+            impl $crate::dsl::IndexKey for $key_type {
+                fn name_suffix(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+display_index_key!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, char, bool, String);
+
+impl IndexKey for &str {
+    fn name_suffix(&self) -> String {
+        self.to_string()
+    }
+}
+
+macro_rules! tuple_index_key {
+    ($($elem: ident: $index: tt),*) => {
+        impl<$($elem),*> IndexKey for ($($elem,)*) where $($elem: Display + Hash + Eq + Clone),* {
+            fn name_suffix(&self) -> String {
+                vec![$(self.$index.to_string()),*].join("_")
+            }
+        }
+    };
+}
+tuple_index_key!(A: 0, B: 1);
+tuple_index_key!(A: 0, B: 1, C: 2);
+tuple_index_key!(A: 0, B: 1, C: 2, D: 3);
+
+/// Dictionary of variables of the same type, indexed by keys.
+///
+/// Variables are named `{name}_{key}` and are kept in the order of the keys given at creation.
+///
+/// # Examples
+///
+/// ```
+/// use lp_modeler::dsl::*;
+///
+/// let machines = vec!["m1", "m2"];
+/// let jobs = vec![1, 2, 3];
+/// let keys: Vec<(&str, i32)> = machines.iter()
+///     .flat_map(|&m| jobs.iter().map(move |&j| (m, j)))
+///     .collect();
+///
+/// let ref x = LpVarDict::binary("x", keys);
+/// assert_eq!(x[&("m1", 2)].name, "x_m1_2");
+///
+/// let mut problem = LpProblem::new("Scheduling", LpObjective::Minimize);
+/// problem += x.sum();
+/// // each job is done on exactly one machine
+/// for (_, jobs_sum) in x.sum_by(|&(_, j)| j) {
+///     problem += jobs_sum.equal(1);
+/// }
+/// // machine m1 runs at most 2 jobs
+/// problem += x.sum_where(|&(m, _)| m == "m1").le(2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LpVarDict<K: IndexKey, V> {
+    name: String,
+    keys: Vec<K>,
+    vars: HashMap<K, V>,
+}
+
+impl<K: IndexKey, V> LpVarDict<K, V> {
+    /// Create a dictionary calling `make_var` with the generated name of each variable
+    pub fn new<I, F>(name: &str, keys: I, make_var: F) -> LpVarDict<K, V>
+    where
+        I: IntoIterator<Item = K>,
+        F: Fn(&str) -> V,
+    {
+        let mut dict = LpVarDict {
+            name: name.to_string(),
+            keys: Vec::new(),
+            vars: HashMap::new(),
+        };
+        for key in keys {
+            if !dict.vars.contains_key(&key) {
+                let var = make_var(&dict.var_name(&key));
+                dict.vars.insert(key.clone(), var);
+                dict.keys.push(key);
+            }
+        }
+        dict
+    }
+
+    /// Name of the variable stored (or that would be stored) at `key`
+    pub fn var_name(&self, key: &K) -> String {
+        format!("{}_{}", self.name, key.name_suffix())
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.vars.get(key)
+    }
+
+    pub fn keys(&self) -> &[K] {
+        &self.keys
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Iterate over the `(key, variable)` pairs in key order
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.keys.iter().map(move |k| (k, &self.vars[k]))
+    }
+
+    /// Variables whose key satisfies `predicate`, in key order
+    pub fn slice<P>(&self, predicate: P) -> Vec<&V>
+    where
+        P: Fn(&K) -> bool,
+    {
+        self.iter()
+            .filter(|&(k, _)| predicate(k))
+            .map(|(_, v)| v)
+            .collect()
+    }
+}
+
+impl<K: IndexKey, V> LpVarDict<K, V>
+where
+    for<'a> &'a V: Into<LpExpression>,
+{
+    /// Sum of the variables whose key satisfies `predicate`
+    pub fn sum_where<P>(&self, predicate: P) -> LpExpression
+    where
+        P: Fn(&K) -> bool,
+    {
+        lp_sum(&self.slice(predicate))
+    }
+
+    /// Sum the variables grouped by `group` (e.g. `|&(i, _)| i` sums over the second index)
+    pub fn sum_by<G, F>(&self, group: F) -> Vec<(G, LpExpression)>
+    where
+        G: Hash + Eq + Clone,
+        F: Fn(&K) -> G,
+    {
+        let mut groups: Vec<G> = Vec::new();
+        let mut members: HashMap<G, Vec<&V>> = HashMap::new();
+        for (k, v) in self.iter() {
+            let g = group(k);
+            if !members.contains_key(&g) {
+                groups.push(g.clone());
+            }
+            members.entry(g).or_default().push(v);
+        }
+        groups
+            .into_iter()
+            .map(|g| {
+                let expr = lp_sum(&members[&g]);
+                (g, expr)
+            })
+            .collect()
+    }
+}
+
+impl<K: IndexKey, V> SummableExp for LpVarDict<K, V>
+where
+    for<'a> &'a V: Into<LpExpression>,
+{
+    fn sum(&self) -> LpExpression {
+        self.sum_where(|_| true)
+    }
+}
+
+impl<K: IndexKey> LpVarDict<K, LpBinary> {
+    pub fn binary<I: IntoIterator<Item = K>>(name: &str, keys: I) -> LpVarDict<K, LpBinary> {
+        LpVarDict::new(name, keys, LpBinary::new)
+    }
+}
+
+impl<K: IndexKey> LpVarDict<K, LpInteger> {
+    pub fn integer<I: IntoIterator<Item = K>>(name: &str, keys: I) -> LpVarDict<K, LpInteger> {
+        LpVarDict::new(name, keys, LpInteger::new)
+    }
+}
+
+impl<K: IndexKey> LpVarDict<K, LpContinuous> {
+    pub fn continuous<I: IntoIterator<Item = K>>(name: &str, keys: I) -> LpVarDict<K, LpContinuous> {
+        LpVarDict::new(name, keys, LpContinuous::new)
+    }
+}
+
+impl<K: IndexKey, V: BoundableLp> LpVarDict<K, V> {
+    /// Set the same lower bound on every variable
+    pub fn lower_bound<N: Into<f64>>(&self, lw: N) -> LpVarDict<K, V> {
+        let lw = lw.into();
+        self.map_vars(|v| v.lower_bound(lw))
+    }
+    /// Set the same upper bound on every variable
+    pub fn upper_bound<N: Into<f64>>(&self, up: N) -> LpVarDict<K, V> {
+        let up = up.into();
+        self.map_vars(|v| v.upper_bound(up))
+    }
+    fn map_vars<F: Fn(&V) -> V>(&self, f: F) -> LpVarDict<K, V> {
+        LpVarDict {
+            name: self.name.clone(),
+            keys: self.keys.clone(),
+            vars: self.vars.iter().map(|(k, v)| (k.clone(), f(v))).collect(),
+        }
+    }
+}
+
+impl<'a, K: IndexKey, V> Index<&'a K> for LpVarDict<K, V> {
+    type Output = V;
+    fn index(&self, key: &'a K) -> &V {
+        self.vars.get(key).expect("No variable found for this key.")
+    }
+}
@@ -0,0 +1,198 @@
+//! Sparse canonical form of linear expressions.
+use std::collections::HashMap;
+use std::iter::FromIterator;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use dsl::{LpExprNode, LpExpression};
+
+/// Linear expression stored as its variables with their coefficients, merged by name, and a
+/// constant.
+///
+/// `LpExpression` remains a tree, in which `+` on an owned expression appends its right
+/// operand. This type is a separate accumulator, merging repeated variables as the terms are
+/// added, which converts into an `LpExpression` (or is used directly) in objectives and
+/// constraints.
+///
+/// # Examples
+///
+/// ```
+/// use lp_modeler::dsl::*;
+///
+/// let vars: Vec<LpContinuous> = (0..1000).map(|i| LpContinuous::new(&format!("x{}", i))).collect();
+///
+/// let mut objective = LpLinearExpression::new();
+/// for (i, var) in vars.iter().enumerate() {
+///     objective.add_term(var, i as f64);
+/// }
+/// objective += &vars[0];
+/// assert_eq!(objective.coefficient("x0"), 1.0);
+///
+/// let mut problem = LpProblem::new("Large", LpObjective::Maximize);
+/// problem += objective;
+/// let capacity: LpLinearExpression = vars.iter().map(|var| (var, 1.0)).collect();
+/// problem += capacity.le(100);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LpLinearExpression {
+    terms: Vec<(LpExprNode, f64)>,
+    positions: HashMap<String, usize>,
+    constant: f64,
+}
+
+impl LpLinearExpression {
+    pub fn new() -> LpLinearExpression {
+        LpLinearExpression::default()
+    }
+
+    /// Add `coefficient` times a variable, a number or a linear expression.
+    /// Panics if the term has products of variables.
+    pub fn add_term<T: Into<LpExpression>>(&mut self, term: T, coefficient: f64) {
+        let expr: LpExpression = term.into();
+        match expr.get_root_expr_ref() {
+            LpExprNode::LitVal(value) => self.constant += coefficient * value,
+            LpExprNode::LpCompExpr(..) | LpExprNode::EmptyExpr => {
+                let (terms, constant) = expr.linear_terms()
+                    .expect("Only linear terms can be added to a linear expression");
+                for (var, var_coefficient) in terms {
+                    self.add_var(var, coefficient * var_coefficient);
+                }
+                self.constant += coefficient * constant;
+            }
+            var => self.add_var(var.clone(), coefficient),
+        }
+    }
+
+    fn add_var(&mut self, var: LpExprNode, coefficient: f64) {
+        let name = var.var_name().unwrap().to_string();
+        match self.positions.get(&name) {
+            Some(&position) => self.terms[position].1 += coefficient,
+            None => {
+                self.positions.insert(name, self.terms.len());
+                self.terms.push((var, coefficient));
+            }
+        }
+    }
+
+    /// Add `factor` times `other`
+    pub fn add_scaled(&mut self, other: &LpLinearExpression, factor: f64) {
+        for (var, coefficient) in &other.terms {
+            self.add_var(var.clone(), factor * coefficient);
+        }
+        self.constant += factor * other.constant;
+    }
+
+    pub fn add_constant(&mut self, constant: f64) {
+        self.constant += constant;
+    }
+
+    /// Coefficient of the variable named `name` (0 if absent)
+    pub fn coefficient(&self, name: &str) -> f64 {
+        self.positions.get(name).map(|&position| self.terms[position].1).unwrap_or(0.0)
+    }
+
+    pub fn constant(&self) -> f64 {
+        self.constant
+    }
+
+    /// Names of the variables with their coefficients, in the order they were first added
+    pub fn terms(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.terms.iter().map(|(var, coefficient)| (var.var_name().unwrap(), *coefficient))
+    }
+
+    pub(crate) fn var_terms(&self) -> &[(LpExprNode, f64)] {
+        &self.terms
+    }
+
+    /// Number of variables
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+}
+
+impl LpExpression {
+    /// Canonical form of a linear expression, or `Err` if it has products of variables
+    pub fn to_linear(&self) -> Result<LpLinearExpression, String> {
+        let (terms, constant) = self.linear_terms()?;
+        let mut linear = LpLinearExpression::new();
+        for (var, coefficient) in terms {
+            linear.add_var(var, coefficient);
+        }
+        linear.constant = constant;
+        Ok(linear)
+    }
+}
+
+impl From<LpLinearExpression> for LpExpression {
+    fn from(linear: LpLinearExpression) -> Self {
+        (&linear).into()
+    }
+}
+
+impl<'a> From<&'a LpLinearExpression> for LpExpression {
+    fn from(linear: &'a LpLinearExpression) -> Self {
+        if linear.terms.is_empty() {
+            return LpExpression::literal(linear.constant);
+        }
+        LpExpression::from_linear_terms(&linear.terms, linear.constant)
+    }
+}
+
+impl<T: Into<LpExpression>> FromIterator<(T, f64)> for LpLinearExpression {
+    fn from_iter<I: IntoIterator<Item = (T, f64)>>(iter: I) -> Self {
+        let mut linear = LpLinearExpression::new();
+        for (term, coefficient) in iter {
+            linear.add_term(term, coefficient);
+        }
+        linear
+    }
+}
+
+impl<T: Into<LpExpression>> AddAssign<T> for LpLinearExpression {
+    fn add_assign(&mut self, term: T) {
+        self.add_term(term, 1.0);
+    }
+}
+
+impl<T: Into<LpExpression>> SubAssign<T> for LpLinearExpression {
+    fn sub_assign(&mut self, term: T) {
+        self.add_term(term, -1.0);
+    }
+}
+
+impl Add for LpLinearExpression {
+    type Output = LpLinearExpression;
+    fn add(mut self, other: LpLinearExpression) -> LpLinearExpression {
+        self.add_scaled(&other, 1.0);
+        self
+    }
+}
+
+impl Sub for LpLinearExpression {
+    type Output = LpLinearExpression;
+    fn sub(mut self, other: LpLinearExpression) -> LpLinearExpression {
+        self.add_scaled(&other, -1.0);
+        self
+    }
+}
+
+impl Mul<f64> for LpLinearExpression {
+    type Output = LpLinearExpression;
+    fn mul(mut self, factor: f64) -> LpLinearExpression {
+        for term in &mut self.terms {
+            term.1 *= factor;
+        }
+        self.constant *= factor;
+        self
+    }
+}
+
+impl Neg for LpLinearExpression {
+    type Output = LpLinearExpression;
+    fn neg(self) -> LpLinearExpression {
+        self * -1.0
+    }
+}
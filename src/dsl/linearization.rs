@@ -0,0 +1,152 @@
+//! Linear reformulations of absolute values, minimums, maximums and products with a binary.
+//!
+//! Each function adds auxiliary variables and constraints prefixed by `name` to the problem
+//! and returns an expression equal to the result, usable in objectives and constraints.
+//! The big-M coefficients are computed from the bounds of the variables, which must be finite.
+use dsl::{lp_sum, BoundableLp, LpBinary, LpContinuous, LpExpression, LpOperations, LpProblem};
+
+/// Finite range of `expr`, used as big-M
+fn finite_bounds(expr: &LpExpression, name: &str) -> Result<(f64, f64), String> {
+    match expr.bounds()? {
+        (Some(lower), Some(upper)) => Ok((lower, upper)),
+        _ => Err(format!("Cannot linearize {}: the expression is unbounded", name)),
+    }
+}
+
+/// Absolute value of `expr`.
+///
+/// The expression is split into a positive and a negative part, and a binary `{name}_sign`
+/// selects which one can be nonzero.
+///
+/// # Examples
+///
+/// ```
+/// use lp_modeler::dsl::*;
+///
+/// let ref x = LpContinuous::new("x").lower_bound(0).upper_bound(10);
+/// let ref y = LpContinuous::new("y").lower_bound(0).upper_bound(10);
+///
+/// let mut problem = LpProblem::new("Distance", LpObjective::Maximize);
+/// let distance = lp_abs(&mut problem, "distance", x - y).unwrap();
+/// problem += distance - x;
+/// ```
+pub fn lp_abs<T>(problem: &mut LpProblem, name: &str, expr: T) -> Result<LpExpression, String>
+where
+    T: Into<LpExpression>,
+{
+    let expr: LpExpression = expr.into();
+    let (lower, upper) = finite_bounds(&expr, name)?;
+    if lower >= 0.0 {
+        return Ok(expr);
+    }
+    if upper <= 0.0 {
+        return Ok(-1.0 * expr);
+    }
+    let positive = &LpContinuous::new(&format!("{}_pos", name)).lower_bound(0.0).upper_bound(upper);
+    let negative = &LpContinuous::new(&format!("{}_neg", name)).lower_bound(0.0).upper_bound(-lower);
+    let sign = &LpBinary::new(&format!("{}_sign", name));
+    *problem += expr.equal(positive - negative).with_name(&format!("{}_split", name));
+    *problem += positive.le(upper * sign).with_name(&format!("{}_pos_max", name));
+    *problem += negative.le(-lower * (1 - sign)).with_name(&format!("{}_neg_max", name));
+    Ok(positive + negative)
+}
+
+/// Maximum of `exprs`.
+///
+/// The result `{name}` is above every expression, and equal to the one selected by the
+/// binaries `{name}_select_k`.
+///
+/// # Examples
+///
+/// ```
+/// use lp_modeler::dsl::*;
+///
+/// let ref x = LpContinuous::new("x").lower_bound(0).upper_bound(10);
+/// let ref y = LpContinuous::new("y").lower_bound(0).upper_bound(10);
+///
+/// let mut problem = LpProblem::new("Makespan", LpObjective::Minimize);
+/// let makespan = lp_max(&mut problem, "makespan", &[x + 2, y + 3]).unwrap();
+/// problem += makespan;
+/// problem += (x + y).ge(12);
+/// ```
+pub fn lp_max<T>(problem: &mut LpProblem, name: &str, exprs: &[T]) -> Result<LpExpression, String>
+where
+    T: Into<LpExpression> + Clone,
+{
+    extremum(problem, name, exprs, true)
+}
+
+/// Minimum of `exprs`.
+///
+/// The result `{name}` is below every expression, and equal to the one selected by the
+/// binaries `{name}_select_k`.
+pub fn lp_min<T>(problem: &mut LpProblem, name: &str, exprs: &[T]) -> Result<LpExpression, String>
+where
+    T: Into<LpExpression> + Clone,
+{
+    extremum(problem, name, exprs, false)
+}
+
+fn extremum<T>(problem: &mut LpProblem, name: &str, exprs: &[T], maximum: bool) -> Result<LpExpression, String>
+where
+    T: Into<LpExpression> + Clone,
+{
+    let exprs: Vec<LpExpression> = exprs.iter().map(|e| e.clone().into()).collect();
+    if exprs.is_empty() {
+        return Err(format!("Cannot linearize {}: no expression", name));
+    }
+    let bounds = exprs.iter()
+        .map(|e| finite_bounds(e, name))
+        .collect::<Result<Vec<_>, String>>()?;
+    let pick = |a: f64, b: f64| if maximum { a.max(b) } else { a.min(b) };
+    let lower = bounds.iter().map(|&(l, _)| l).fold(bounds[0].0, pick);
+    let upper = bounds.iter().map(|&(_, u)| u).fold(bounds[0].1, pick);
+    let output = &LpContinuous::new(name).lower_bound(lower).upper_bound(upper);
+    let selectors: Vec<LpBinary> = (0..exprs.len())
+        .map(|k| LpBinary::new(&format!("{}_select_{}", name, k)))
+        .collect();
+    for (k, ((expr, &(expr_lower, expr_upper)), select)) in exprs.iter().zip(bounds.iter()).zip(selectors.iter()).enumerate() {
+        // the result can only move away from a selected expression by the widest gap possible
+        if maximum {
+            let gap = upper - expr_lower;
+            *problem += output.ge(expr.clone()).with_name(&format!("{}_above_{}", name, k));
+            *problem += output.le(expr + gap - gap * select).with_name(&format!("{}_select_{}_le", name, k));
+        } else {
+            let gap = expr_upper - lower;
+            *problem += output.le(expr.clone()).with_name(&format!("{}_below_{}", name, k));
+            *problem += output.ge(expr - gap + gap * select).with_name(&format!("{}_select_{}_ge", name, k));
+        }
+    }
+    *problem += lp_sum(&selectors).equal(1).with_name(&format!("{}_select", name));
+    Ok(output.into())
+}
+
+/// Product of `binary` and `expr`, e.g. of two binaries.
+///
+/// The result `{name}` is zero when the binary is zero and equal to `expr` otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use lp_modeler::dsl::*;
+///
+/// let ref a = LpBinary::new("a");
+/// let ref b = LpBinary::new("b");
+///
+/// let mut problem = LpProblem::new("Pairs", LpObjective::Maximize);
+/// let both = lp_product(&mut problem, "both", a, b).unwrap();
+/// problem += 3 * both - a - b;
+/// ```
+pub fn lp_product<T>(problem: &mut LpProblem, name: &str, binary: &LpBinary, expr: T) -> Result<LpExpression, String>
+where
+    T: Into<LpExpression>,
+{
+    let expr: LpExpression = expr.into();
+    let (lower, upper) = finite_bounds(&expr, name)?;
+    let output = &LpContinuous::new(name).lower_bound(lower.min(0.0)).upper_bound(upper.max(0.0));
+    *problem += output.le(upper * binary).with_name(&format!("{}_zero_le", name));
+    *problem += output.ge(lower * binary).with_name(&format!("{}_zero_ge", name));
+    *problem += output.le(&expr - lower + lower * binary).with_name(&format!("{}_copy_le", name));
+    *problem += output.ge(&expr - upper + upper * binary).with_name(&format!("{}_copy_ge", name));
+    Ok(output.into())
+}
@@ -0,0 +1,109 @@
+//! Logical rules over binaries.
+//!
+//! Rules between existing binaries are plain constraints (`a.implies(b)`, `at_most_one(..)`).
+//! Logical operators (`lp_and`, `lp_or`, `lp_xor`, `lp_not`) create a binary equal to their
+//! result, whose defining constraints prefixed by `name` are added to the problem, so that
+//! they can be combined.
+//!
+//! # Examples
+//!
+//! ```
+//! use lp_modeler::dsl::*;
+//!
+//! let ref morning = LpBinary::new("morning");
+//! let ref evening = LpBinary::new("evening");
+//! let ref night = LpBinary::new("night");
+//! let ref overtime = LpBinary::new("overtime");
+//!
+//! let mut problem = LpProblem::new("Shifts", LpObjective::Minimize);
+//! problem += overtime;
+//! // nobody works the night after the evening shift
+//! problem += evening.excludes(night);
+//! // working the morning and the night is overtime
+//! let ref long_day = lp_and(&mut problem, "long_day", &[morning.clone(), night.clone()]);
+//! problem += long_day.implies(overtime);
+//! problem += exactly_k(&[morning.clone(), evening.clone(), night.clone()], 2);
+//! ```
+use dsl::{lp_sum, LpBinary, LpConstraint, LpOperations, LpProblem};
+
+/// Rules between two binaries
+pub trait LpLogicalOperations {
+    /// `other` is 1 whenever `self` is 1
+    fn implies(&self, other: &LpBinary) -> LpConstraint;
+    /// `self` and `other` are not both 1
+    fn excludes(&self, other: &LpBinary) -> LpConstraint;
+    /// `self` and `other` take the same value
+    fn equivalent(&self, other: &LpBinary) -> LpConstraint;
+}
+
+impl LpLogicalOperations for LpBinary {
+    fn implies(&self, other: &LpBinary) -> LpConstraint {
+        self.le(other)
+    }
+
+    fn excludes(&self, other: &LpBinary) -> LpConstraint {
+        (self + other).le(1)
+    }
+
+    fn equivalent(&self, other: &LpBinary) -> LpConstraint {
+        self.equal(other)
+    }
+}
+
+/// At most `k` of the binaries are 1
+pub fn at_most_k(binaries: &[LpBinary], k: u32) -> LpConstraint {
+    lp_sum(&binaries.to_vec()).le(k as f64)
+}
+
+/// At least `k` of the binaries are 1
+pub fn at_least_k(binaries: &[LpBinary], k: u32) -> LpConstraint {
+    lp_sum(&binaries.to_vec()).ge(k as f64)
+}
+
+/// Exactly `k` of the binaries are 1
+pub fn exactly_k(binaries: &[LpBinary], k: u32) -> LpConstraint {
+    lp_sum(&binaries.to_vec()).equal(k as f64)
+}
+
+/// At most one of the binaries is 1
+pub fn at_most_one(binaries: &[LpBinary]) -> LpConstraint {
+    at_most_k(binaries, 1)
+}
+
+/// Binary `name` equal to 1 if and only if all the `binaries` are 1
+pub fn lp_and(problem: &mut LpProblem, name: &str, binaries: &[LpBinary]) -> LpBinary {
+    let result = LpBinary::new(name);
+    for (k, binary) in binaries.iter().enumerate() {
+        *problem += result.le(binary).with_name(&format!("{}_{}", name, k));
+    }
+    let others = binaries.len() as f64 - 1.0;
+    *problem += result.ge(lp_sum(&binaries.to_vec()) - others).with_name(&format!("{}_all", name));
+    result
+}
+
+/// Binary `name` equal to 1 if and only if at least one of the `binaries` is 1
+pub fn lp_or(problem: &mut LpProblem, name: &str, binaries: &[LpBinary]) -> LpBinary {
+    let result = LpBinary::new(name);
+    for (k, binary) in binaries.iter().enumerate() {
+        *problem += result.ge(binary).with_name(&format!("{}_{}", name, k));
+    }
+    *problem += result.le(lp_sum(&binaries.to_vec())).with_name(&format!("{}_any", name));
+    result
+}
+
+/// Binary `name` equal to 1 if and only if exactly one of `a` and `b` is 1
+pub fn lp_xor(problem: &mut LpProblem, name: &str, a: &LpBinary, b: &LpBinary) -> LpBinary {
+    let result = &LpBinary::new(name);
+    *problem += result.le(a + b).with_name(&format!("{}_any", name));
+    *problem += result.le(2 - a - b).with_name(&format!("{}_not_both", name));
+    *problem += result.ge(a - b).with_name(&format!("{}_only_0", name));
+    *problem += result.ge(b - a).with_name(&format!("{}_only_1", name));
+    result.clone()
+}
+
+/// Binary `name` equal to 1 if and only if `binary` is 0
+pub fn lp_not(problem: &mut LpProblem, name: &str, binary: &LpBinary) -> LpBinary {
+    let result = LpBinary::new(name);
+    *problem += (&result + binary).equal(1).with_name(&format!("{}_not", name));
+    result
+}
@@ -0,0 +1,119 @@
+//! Several objectives optimized by decreasing priority, those sharing a priority being
+//! blended by their weights.
+use dsl::{LpExpression, LpProblem};
+
+/// Objective of a problem with several objectives, all optimized in the direction of the
+/// problem (`LpProblem::objective_type`).
+///
+/// The objectives of the highest priority are optimized first. Optimizing the next
+/// priorities may degrade the value reached by at most `max(abs_tol, rel_tol * |value|)`.
+///
+/// # Examples
+///
+/// ```
+/// use lp_modeler::dsl::*;
+///
+/// let ref overtime = LpContinuous::new("overtime").lower_bound(0.0);
+/// let ref cost = LpContinuous::new("cost").lower_bound(0.0);
+///
+/// let mut problem = LpProblem::new("Staffing", LpObjective::Minimize);
+/// problem += LpMultiObjective::new("overtime", overtime).with_priority(2).with_abs_tol(1.0);
+/// problem += LpMultiObjective::new("cost", cost).with_priority(1);
+/// problem += (overtime + cost).ge(10);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LpMultiObjective {
+    pub name: String,
+    pub expr: LpExpression,
+    pub priority: i32,
+    pub weight: f64,
+    pub abs_tol: f64,
+    pub rel_tol: f64,
+}
+
+impl LpMultiObjective {
+    /// Objective of priority 0 and weight 1, which cannot be degraded
+    pub fn new<T: Into<LpExpression>>(name: &str, expr: T) -> LpMultiObjective {
+        LpMultiObjective {
+            name: name.to_string(),
+            expr: expr.into(),
+            priority: 0,
+            weight: 1.0,
+            abs_tol: 0.0,
+            rel_tol: 0.0,
+        }
+    }
+
+    pub fn with_priority(&self, priority: i32) -> LpMultiObjective {
+        LpMultiObjective { priority, ..self.clone() }
+    }
+
+    pub fn with_weight(&self, weight: f64) -> LpMultiObjective {
+        LpMultiObjective { weight, ..self.clone() }
+    }
+
+    /// Absolute degradation allowed when optimizing the lower priorities
+    pub fn with_abs_tol(&self, abs_tol: f64) -> LpMultiObjective {
+        LpMultiObjective { abs_tol, ..self.clone() }
+    }
+
+    /// Degradation allowed when optimizing the lower priorities, relative to the value reached
+    pub fn with_rel_tol(&self, rel_tol: f64) -> LpMultiObjective {
+        LpMultiObjective { rel_tol, ..self.clone() }
+    }
+}
+
+/// Objectives of the same priority blended by their weights
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LpObjectiveLevel {
+    pub(crate) priority: i32,
+    pub(crate) expr: LpExpression,
+    /// Largest tolerances of the objectives of the level
+    pub(crate) abs_tol: f64,
+    pub(crate) rel_tol: f64,
+}
+
+impl LpProblem {
+    /// Add an objective to a problem with several objectives
+    pub fn add_objective(&mut self, objective: LpMultiObjective) {
+        self.objectives.push(objective);
+    }
+
+    /// Whether objectives were added with `add_objective`
+    pub fn has_multi_objectives(&self) -> bool {
+        !self.objectives.is_empty()
+    }
+
+    /// Objectives of a problem with several objectives. An objective set with `+=` comes
+    /// first, named `obj`, with priority 0 and weight 1.
+    pub fn multi_objectives(&self) -> Vec<LpMultiObjective> {
+        self.obj_expr_arena.iter()
+            .map(|expr| LpMultiObjective::new("obj", expr))
+            .chain(self.objectives.iter().cloned())
+            .collect()
+    }
+
+    /// Levels of priority, from the highest
+    pub(crate) fn objective_levels(&self) -> Vec<LpObjectiveLevel> {
+        let mut objectives = self.multi_objectives();
+        objectives.sort_by_key(|objective| -objective.priority);
+        let mut levels: Vec<LpObjectiveLevel> = Vec::new();
+        for objective in objectives {
+            let weighted = objective.weight * objective.expr;
+            match levels.last_mut() {
+                Some(level) if level.priority == objective.priority => {
+                    level.expr = &level.expr + weighted;
+                    level.abs_tol = level.abs_tol.max(objective.abs_tol);
+                    level.rel_tol = level.rel_tol.max(objective.rel_tol);
+                }
+                _ => levels.push(LpObjectiveLevel {
+                    priority: objective.priority,
+                    expr: weighted,
+                    abs_tol: objective.abs_tol,
+                    rel_tol: objective.rel_tol,
+                }),
+            }
+        }
+        levels
+    }
+}
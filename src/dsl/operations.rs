@@ -1,6 +1,6 @@
 use std::ops::{Add, Mul, Neg, Sub, AddAssign, SubAssign, MulAssign};
 use dsl::LpExprNode::*;
-use dsl::{Constraint, LpBinary, LpConstraint, LpContinuous, LpExprNode, LpInteger, LpExpression};
+use dsl::{Constraint, LpBinary, LpConstraint, LpContinuous, LpExprNode, LpInteger, LpExpression, LpSemiContinuous, LpSemiInteger};
 use dsl::LpExprOp::{Addition, Subtraction, Multiplication};
 
 /// Operations trait for any type implementing Into<LpExpression> trait
@@ -13,6 +13,17 @@ pub trait LpOperations<T> where T: Into<LpExpression> {
     fn equal(&self, lhs_expr: T) -> LpConstraint;
 }
 
+/// Two-sided constraint syntax for any type implementing Into<LpExpression>
+pub trait LpRangeOperations {
+    /// Range constraint `lower <= self <= upper`, kept as a single row.
+    /// Panics if `upper - lower` is not a constant. A lower bound greater than the upper
+    /// bound is reported by `LpProblem::validate`.
+    fn between<L, U>(&self, lower: L, upper: U) -> LpConstraint
+    where
+        L: Into<LpExpression>,
+        U: Into<LpExpression>;
+}
+
 /// Macro implementing binary operations for Into<LpExpression> or &Into<LpExpression>
 macro_rules! operations_for_expr {
     ($trait_name: ident, $f_name: ident, $expr_type: ident) => {
@@ -22,8 +33,7 @@ macro_rules! operations_for_expr {
         {
             type Output = LpExpression;
             fn $f_name(self, not_yet_lp_expr_arena: T) -> LpExpression {
-                let new_lp_expr_arena = self.clone();
-                new_lp_expr_arena.merge_cloned_arenas(&not_yet_lp_expr_arena.into(), $expr_type)
+                self.merge_arenas(&not_yet_lp_expr_arena.into(), $expr_type)
             }
         }
         impl<'a, T> $trait_name<T> for &'a LpExpression
@@ -32,8 +42,7 @@ macro_rules! operations_for_expr {
         {
             type Output = LpExpression;
             fn $f_name(self, not_yet_lp_expr_arena: T) -> LpExpression {
-                let new_lp_expr_arena = (*self).clone();
-                new_lp_expr_arena.merge_cloned_arenas(&not_yet_lp_expr_arena.into(), $expr_type)
+                self.merge_cloned_arenas(&not_yet_lp_expr_arena.into(), $expr_type)
             }
         }
     };
@@ -50,7 +59,8 @@ macro_rules! assign_operations_for_expr {
             T: Into<LpExpression> + Clone,
         {
             fn $f_name(&mut self, rhs: T) {
-                *self = self.merge_cloned_arenas(&rhs.into(), $expr_type)
+                let lhs = std::mem::replace(self, LpExpression::new());
+                *self = lhs.merge_arenas(&rhs.into(), $expr_type)
             }
         }
     };
@@ -69,8 +79,8 @@ macro_rules! lpvars_operation_for_intoexpr {
         {
             type Output = LpExpression;
             fn $f_name(self, not_yet_lp_expr_arena: T) -> LpExpression {
-                let new_lp_expr_arena: LpExpression = self.clone().into();
-                new_lp_expr_arena.merge_cloned_arenas(&not_yet_lp_expr_arena.into(), $expr_type)
+                let new_lp_expr_arena: LpExpression = self.into();
+                new_lp_expr_arena.merge_arenas(&not_yet_lp_expr_arena.into(), $expr_type)
             }
         }
         impl<'a, T> $trait_name<T> for &'a $lp_type
@@ -80,7 +90,7 @@ macro_rules! lpvars_operation_for_intoexpr {
             type Output = LpExpression;
             fn $f_name(self, not_yet_lp_expr_arena: T) -> LpExpression {
                 let new_lp_expr_arena: LpExpression = (*self).clone().into();
-                new_lp_expr_arena.merge_cloned_arenas(&not_yet_lp_expr_arena.into(), $expr_type)
+                new_lp_expr_arena.merge_arenas(&not_yet_lp_expr_arena.into(), $expr_type)
             }
         }
     };
@@ -95,6 +105,12 @@ lpvars_operation_for_intoexpr!(Sub, sub, LpInteger, Subtraction);
 lpvars_operation_for_intoexpr!(Mul, mul, LpContinuous, Multiplication);
 lpvars_operation_for_intoexpr!(Add, add, LpContinuous, Addition);
 lpvars_operation_for_intoexpr!(Sub, sub, LpContinuous, Subtraction);
+lpvars_operation_for_intoexpr!(Mul, mul, LpSemiContinuous, Multiplication);
+lpvars_operation_for_intoexpr!(Add, add, LpSemiContinuous, Addition);
+lpvars_operation_for_intoexpr!(Sub, sub, LpSemiContinuous, Subtraction);
+lpvars_operation_for_intoexpr!(Mul, mul, LpSemiInteger, Multiplication);
+lpvars_operation_for_intoexpr!(Add, add, LpSemiInteger, Addition);
+lpvars_operation_for_intoexpr!(Sub, sub, LpSemiInteger, Subtraction);
 
 /// Macro implementing binary operations for a numeric type
 macro_rules! numeric_operation_for_expr {
@@ -102,15 +118,15 @@ macro_rules! numeric_operation_for_expr {
         impl $trait_name<LpExpression> for $num_type {
             type Output = LpExpression;
             fn $f_name(self, lp_expr_arena: LpExpression) -> LpExpression {
-                let new_lp_expr_arena: LpExpression = (self as f32).into();
-                new_lp_expr_arena.merge_cloned_arenas(&lp_expr_arena.clone(), $type_expr)
+                let new_lp_expr_arena: LpExpression = (self as f64).into();
+                new_lp_expr_arena.merge_arenas(&lp_expr_arena, $type_expr)
             }
         }
         impl<'a> $trait_name<&'a LpExpression> for $num_type {
             type Output = LpExpression;
             fn $f_name(self, lp_expr_arena: &'a LpExpression) -> LpExpression {
-                let new_lp_expr_arena: LpExpression = (self as f32).into();
-                new_lp_expr_arena.merge_cloned_arenas(lp_expr_arena, $type_expr)
+                let new_lp_expr_arena: LpExpression = (self as f64).into();
+                new_lp_expr_arena.merge_arenas(lp_expr_arena, $type_expr)
             }
         }
     };
@@ -123,7 +139,7 @@ macro_rules! numeric_all_ops_for_expr {
         numeric_operation_for_expr!($num_type, Sub, sub, Subtraction);
     };
 }
-numeric_all_ops_for_expr!(f32);
+numeric_all_ops_for_expr!(f64);
 numeric_all_ops_for_expr!(i32);
 
 /// &LpExpression to LpExpression
@@ -136,7 +152,7 @@ impl<'a> Into<LpExpression> for &'a LpExpression {
 /// Implementing LpOperations trait for any Into<LpExpression>
 impl<T: Into<LpExpression> + Clone, U> LpOperations<T> for U where U: Into<LpExpression> + Clone {
     fn le(&self, lhs_expr: T) -> LpConstraint {
-        LpConstraint(
+        LpConstraint::new(
             self.clone().into(),
             Constraint::LessOrEqual,
             lhs_expr.clone().into(),
@@ -144,7 +160,7 @@ impl<T: Into<LpExpression> + Clone, U> LpOperations<T> for U where U: Into<LpExp
         .generalize()
     }
     fn ge(&self, lhs_expr: T) -> LpConstraint {
-        LpConstraint(
+        LpConstraint::new(
             self.clone().into(),
             Constraint::GreaterOrEqual,
             lhs_expr.clone().into(),
@@ -152,7 +168,7 @@ impl<T: Into<LpExpression> + Clone, U> LpOperations<T> for U where U: Into<LpExp
         .generalize()
     }
     fn equal(&self, lhs_expr: T) -> LpConstraint {
-        LpConstraint(
+        LpConstraint::new(
             self.clone().into(),
             Constraint::Equal,
             lhs_expr.clone().into(),
@@ -161,11 +177,28 @@ impl<T: Into<LpExpression> + Clone, U> LpOperations<T> for U where U: Into<LpExp
     }
 }
 
+impl<U> LpRangeOperations for U where U: Into<LpExpression> + Clone {
+    fn between<L, V>(&self, lower: L, upper: V) -> LpConstraint
+    where
+        L: Into<LpExpression>,
+        V: Into<LpExpression>,
+    {
+        let upper: LpExpression = upper.into();
+        let (terms, width) = (&upper - lower.into())
+            .linear_terms()
+            .expect("The bounds of a range constraint must be linear.");
+        if terms.iter().any(|&(_, coefficient)| coefficient != 0.0) {
+            panic!("The bounds of a range constraint must differ by a constant.");
+        }
+        LpConstraint::new(self.clone().into(), Constraint::Range(width), upper).generalize()
+    }
+}
+
 impl<'a> Neg for &'a LpExprNode {
     type Output = LpExpression;
     fn neg(self) -> LpExpression {
         let new_lp_expr_arena: LpExpression = LitVal(-1.0).into();
-        new_lp_expr_arena.merge_cloned_arenas(&self.clone().into(), Multiplication)
+        new_lp_expr_arena.merge_arenas(&self.clone().into(), Multiplication)
     }
 }
 
@@ -175,7 +208,7 @@ macro_rules! neg_operation_for_lpvars {
             type Output = LpExpression;
             fn neg(self) -> LpExpression {
                 let new_lp_expr_arena: LpExpression = LitVal(-1.0).into();
-                new_lp_expr_arena.merge_cloned_arenas(&self.clone().into(), Multiplication)
+                new_lp_expr_arena.merge_arenas(&self.clone().into(), Multiplication)
             }
         }
     };
@@ -183,6 +216,8 @@ macro_rules! neg_operation_for_lpvars {
 neg_operation_for_lpvars!(LpInteger);
 neg_operation_for_lpvars!(LpContinuous);
 neg_operation_for_lpvars!(LpBinary);
+neg_operation_for_lpvars!(LpSemiContinuous);
+neg_operation_for_lpvars!(LpSemiInteger);
 
 /// Macro implementing binary operations for a numeric type
 macro_rules! numeric_operation_for_lpvars {
@@ -190,17 +225,17 @@ macro_rules! numeric_operation_for_lpvars {
         impl $trait_name<$lp_type_right> for $num_type_left {
             type Output = LpExpression;
             fn $f_name(self, var: $lp_type_right) -> LpExpression {
-                let new_lp_expr_arena: LpExpression = (self as f32).clone().into();
+                let new_lp_expr_arena: LpExpression = (self as f64).clone().into();
                 let new_right: LpExpression = var.clone().into();
-                new_lp_expr_arena.merge_cloned_arenas(&new_right, $type_expr)
+                new_lp_expr_arena.merge_arenas(&new_right, $type_expr)
             }
         }
         impl<'a> $trait_name<&'a $lp_type_right> for $num_type_left {
             type Output = LpExpression;
             fn $f_name(self, var: &'a $lp_type_right) -> LpExpression {
-                let new_lp_expr_arena: LpExpression = (self as f32).into();
+                let new_lp_expr_arena: LpExpression = (self as f64).into();
                 let new_right: LpExpression = (*var).clone().into();
-                new_lp_expr_arena.merge_cloned_arenas(&new_right, $type_expr)
+                new_lp_expr_arena.merge_arenas(&new_right, $type_expr)
             }
         }
     };
@@ -218,7 +253,13 @@ macro_rules! numeric_all_ops_for_lpvars {
         numeric_operation_for_lpvars!($num_type, Sub, sub, Subtraction, LpInteger);
         numeric_operation_for_lpvars!($num_type, Sub, sub, Subtraction, LpBinary);
         numeric_operation_for_lpvars!($num_type, Sub, sub, Subtraction, LpContinuous);
+        numeric_operation_for_lpvars!($num_type, Add, add, Addition, LpSemiContinuous);
+        numeric_operation_for_lpvars!($num_type, Mul, mul, Multiplication, LpSemiContinuous);
+        numeric_operation_for_lpvars!($num_type, Sub, sub, Subtraction, LpSemiContinuous);
+        numeric_operation_for_lpvars!($num_type, Add, add, Addition, LpSemiInteger);
+        numeric_operation_for_lpvars!($num_type, Mul, mul, Multiplication, LpSemiInteger);
+        numeric_operation_for_lpvars!($num_type, Sub, sub, Subtraction, LpSemiInteger);
     };
 }
 numeric_all_ops_for_lpvars!(i32);
-numeric_all_ops_for_lpvars!(f32);
+numeric_all_ops_for_lpvars!(f64);
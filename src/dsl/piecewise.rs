@@ -0,0 +1,155 @@
+//! Piecewise-linear functions defined by breakpoints.
+use dsl::{lp_sum, BoundableLp, LpBinary, LpContinuous, LpExpression, LpOperations, LpProblem, LpSos, SosType};
+
+/// How a piecewise-linear function is modeled on the problem.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PiecewiseFormulation {
+    /// Convex combination of the breakpoints whose weights form a special ordered set of type 2
+    Sos2,
+    /// Incremental formulation with binaries, for solvers without special ordered sets
+    Incremental,
+    /// One constraint per segment and no integer variable. Only valid for a convex function
+    /// which is minimized (or a concave one which is maximized).
+    Convex,
+}
+
+/// Piecewise-linear function `y = f(x)` interpolating breakpoints `(x, y)`.
+///
+/// # Examples
+///
+/// ```
+/// use lp_modeler::dsl::*;
+///
+/// let ref quantity = LpContinuous::new("quantity");
+///
+/// let mut problem = LpProblem::new("Purchase", LpObjective::Minimize);
+/// // unit price decreases with the quantity bought
+/// let cost = LpPiecewise::new("cost", vec![(0.0, 0.0), (100.0, 500.0), (300.0, 1100.0)])
+///     .apply(&mut problem, quantity)
+///     .unwrap();
+/// problem += cost;
+/// problem += quantity.ge(150);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LpPiecewise {
+    name: String,
+    breakpoints: Vec<(f64, f64)>,
+    formulation: PiecewiseFormulation,
+}
+
+impl LpPiecewise {
+    /// Function interpolating `breakpoints`, sorted by increasing `x`.
+    /// `name` prefixes the auxiliary variables and constraints.
+    pub fn new(name: &str, breakpoints: Vec<(f64, f64)>) -> LpPiecewise {
+        LpPiecewise {
+            name: name.to_string(),
+            breakpoints,
+            formulation: PiecewiseFormulation::Sos2,
+        }
+    }
+
+    pub fn with_formulation(&self, formulation: PiecewiseFormulation) -> LpPiecewise {
+        LpPiecewise {
+            formulation,
+            ..self.clone()
+        }
+    }
+
+    /// Add the variables and constraints modeling `f(input)` to `problem` and return the
+    /// expression of the output. The input is restricted to the range of the breakpoints.
+    pub fn apply<T>(&self, problem: &mut LpProblem, input: T) -> Result<LpExpression, String>
+    where
+        T: Into<LpExpression>,
+    {
+        self.check_breakpoints()?;
+        let input: LpExpression = input.into();
+        match self.formulation {
+            PiecewiseFormulation::Sos2 => Ok(self.apply_sos2(problem, input)),
+            PiecewiseFormulation::Incremental => Ok(self.apply_incremental(problem, input)),
+            PiecewiseFormulation::Convex => self.apply_convex(problem, input),
+        }
+    }
+
+    fn check_breakpoints(&self) -> Result<(), String> {
+        if self.breakpoints.len() < 2 {
+            return Err(format!("Piecewise function {} needs at least two breakpoints", self.name));
+        }
+        if self.breakpoints.windows(2).any(|w| w[0].0 >= w[1].0) {
+            return Err(format!("Breakpoints of {} must have strictly increasing x", self.name));
+        }
+        Ok(())
+    }
+
+    fn slopes(&self) -> Vec<f64> {
+        self.breakpoints
+            .windows(2)
+            .map(|w| (w[1].1 - w[0].1) / (w[1].0 - w[0].0))
+            .collect()
+    }
+
+    fn apply_sos2(&self, problem: &mut LpProblem, input: LpExpression) -> LpExpression {
+        let lambdas: Vec<LpContinuous> = (0..self.breakpoints.len())
+            .map(|k| LpContinuous::new(&format!("{}_lambda_{}", self.name, k)).lower_bound(0.0).upper_bound(1.0))
+            .collect();
+        let weighted = |f: &dyn Fn(&(f64, f64)) -> f64| -> LpExpression {
+            let terms: Vec<LpExpression> = lambdas.iter()
+                .zip(self.breakpoints.iter())
+                .map(|(lambda, point)| f(point) * lambda)
+                .collect();
+            lp_sum(&terms)
+        };
+        let output = weighted(&|&(_, y)| y);
+        *problem += lp_sum(&lambdas).equal(1).with_name(&format!("{}_convexity", self.name));
+        *problem += input.equal(weighted(&|&(x, _)| x)).with_name(&format!("{}_input", self.name));
+        *problem += LpSos::new(
+            &format!("{}_sos", self.name),
+            SosType::Sos2,
+            lambdas.iter().enumerate().map(|(k, lambda)| (lambda, k as f64 + 1.0)).collect(),
+        );
+        output
+    }
+
+    fn apply_incremental(&self, problem: &mut LpProblem, input: LpExpression) -> LpExpression {
+        let segments = self.breakpoints.len() - 1;
+        let deltas: Vec<LpContinuous> = (0..segments)
+            .map(|k| LpContinuous::new(&format!("{}_delta_{}", self.name, k)).lower_bound(0.0).upper_bound(1.0))
+            .collect();
+        let (x0, y0) = self.breakpoints[0];
+        let increments = |f: &dyn Fn(&(f64, f64)) -> f64, start: f64| -> LpExpression {
+            let terms: Vec<LpExpression> = deltas.iter()
+                .zip(self.breakpoints.windows(2))
+                .map(|(delta, w)| (f(&w[1]) - f(&w[0])) * delta)
+                .collect();
+            lp_sum(&terms) + start
+        };
+        // a segment can only be used once the previous ones are filled
+        for k in 0..segments - 1 {
+            let z = &LpBinary::new(&format!("{}_z_{}", self.name, k));
+            *problem += deltas[k + 1].le(z).with_name(&format!("{}_order_{}_next", self.name, k));
+            *problem += z.le(&deltas[k]).with_name(&format!("{}_order_{}", self.name, k));
+        }
+        *problem += input.equal(increments(&|&(x, _)| x, x0)).with_name(&format!("{}_input", self.name));
+        increments(&|&(_, y)| y, y0)
+    }
+
+    fn apply_convex(&self, problem: &mut LpProblem, input: LpExpression) -> Result<LpExpression, String> {
+        let slopes = self.slopes();
+        let convex = slopes.windows(2).all(|w| w[0] <= w[1]);
+        let concave = slopes.windows(2).all(|w| w[0] >= w[1]);
+        if !convex && !concave {
+            return Err(format!("Piecewise function {} is neither convex nor concave", self.name));
+        }
+        let output = &LpContinuous::new(&format!("{}_output", self.name));
+        for (k, (slope, &(x, y))) in slopes.iter().zip(self.breakpoints.iter()).enumerate() {
+            // line of the segment: y + slope * (input - x)
+            let line = *slope * &input + (y - slope * x);
+            let segment = if convex { output.ge(line) } else { output.le(line) };
+            *problem += segment.with_name(&format!("{}_segment_{}", self.name, k));
+        }
+        let (x_min, _) = self.breakpoints[0];
+        let (x_max, _) = self.breakpoints[self.breakpoints.len() - 1];
+        *problem += input.ge(x_min).with_name(&format!("{}_input_min", self.name));
+        *problem += input.le(x_max).with_name(&format!("{}_input_max", self.name));
+        Ok(output.into())
+    }
+}
@@ -16,12 +16,209 @@ use dsl::*;
 ///
 /// let mut problem = LpProblem::new("One Problem", LpObjective::Maximize);
 /// ```
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum LpObjective {
     Minimize,
     Maximize,
 }
 
+/// Type of a special ordered set: at most one (`Sos1`) or at most two consecutive (`Sos2`)
+/// variables of the set can be non-zero.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SosType {
+    Sos1,
+    Sos2,
+}
+
+/// Special ordered set of variables. The weights give the order of the variables
+/// and must be distinct.
+///
+/// # Examples:
+///
+/// ```
+/// use lp_modeler::dsl::*;
+///
+/// let ref a = LpContinuous::new("a");
+/// let ref b = LpContinuous::new("b");
+/// let ref c = LpContinuous::new("c");
+///
+/// let mut problem = LpProblem::new("One Problem", LpObjective::Maximize);
+/// problem += a + b + c;
+/// problem += (a + b + c).le(10);
+/// problem += LpSos::new("abc", SosType::Sos2, vec![(a, 1.0), (b, 2.0), (c, 3.0)]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LpSos {
+    pub name: String,
+    pub sos_type: SosType,
+    pub weighted_vars: Vec<(String, f64)>,
+}
+
+impl LpSos {
+    /// Create a set from `(variable, weight)` pairs.
+    ///
+    /// # Panics
+    ///
+    /// If one of the expressions is not a single variable.
+    pub fn new<T>(name: &str, sos_type: SosType, vars: Vec<(T, f64)>) -> LpSos
+    where
+        T: Into<LpExpression>,
+    {
+        let weighted_vars = vars
+            .into_iter()
+            .map(|(var, weight)| {
+                let expr: LpExpression = var.into();
+                let name = expr
+                    .get_root_expr_ref()
+                    .var_name()
+                    .expect("Special ordered sets must be made of variables")
+                    .to_string();
+                (name, weight)
+            })
+            .collect();
+        LpSos {
+            name: name.to_string(),
+            sos_type,
+            weighted_vars,
+        }
+    }
+}
+
+/// Indicator constraint: when `binary` takes the value `active` (1 for `true`, 0 for `false`),
+/// `constraint` must hold.
+///
+/// Solvers without native support get a big-M reformulation computed from the bounds of the
+/// variables (see `LpProblem::reformulate_indicators`).
+///
+/// # Examples:
+///
+/// ```
+/// use lp_modeler::dsl::*;
+///
+/// let ref x = LpContinuous::new("x").lower_bound(0.0).upper_bound(100.0);
+/// let ref open = LpBinary::new("open");
+///
+/// let mut problem = LpProblem::new("One Problem", LpObjective::Maximize);
+/// problem += x - 10 * open;
+/// // if the site is closed, nothing is produced
+/// problem += LpIndicator::new(open, false, x.le(0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LpIndicator {
+    pub binary: LpBinary,
+    pub active: bool,
+    pub constraint: LpConstraint,
+}
+
+impl LpIndicator {
+    pub fn new(binary: &LpBinary, active: bool, constraint: LpConstraint) -> LpIndicator {
+        LpIndicator {
+            binary: binary.clone(),
+            active,
+            constraint,
+        }
+    }
+
+    /// Big-M constraints equivalent to the indicator, using the bounds of the variables
+    pub fn to_big_m(&self) -> Result<Vec<LpConstraint>, String> {
+        let LpConstraint(ref lhs, ref op, ref rhs, ref name) = self.constraint;
+        let (terms, constant) = (lhs - rhs).linear_terms()?;
+        // range of `lhs - rhs` over the bounds of the variables
+        let mut min = constant;
+        let mut max = constant;
+        for (var, coefficient) in &terms {
+            let name = var.var_name().unwrap_or_default();
+            let (lower, upper) = var.var_bounds().unwrap_or((None, None));
+            let unbounded = || format!("Cannot reformulate the indicator constraint: {} is unbounded", name);
+            let (low, up) = if *coefficient >= 0.0 { (lower, upper) } else { (upper, lower) };
+            if let Constraint::GreaterOrEqual | Constraint::Equal | Constraint::Range(_) = op {
+                min += coefficient * low.ok_or_else(unbounded)?;
+            }
+            if let Constraint::LessOrEqual | Constraint::Equal | Constraint::Range(_) = op {
+                max += coefficient * up.ok_or_else(unbounded)?;
+            }
+        }
+        // a range `-width <= lhs - rhs <= 0` is bounded below by `-width` instead of 0
+        let width = if let Constraint::Range(width) = op { *width } else { 0.0 };
+        // `lhs - rhs <= max * relaxed` and `lhs - rhs >= min * relaxed` with relaxed = 0 when active
+        let relaxed: LpExpression = if self.active {
+            LpExpression::literal(1.0) - &self.binary
+        } else {
+            (&self.binary).into()
+        };
+        let mut constraints = Vec::new();
+        if let Constraint::LessOrEqual | Constraint::Equal | Constraint::Range(_) = op {
+            constraints.push((lhs - rhs - max * &relaxed).le(0));
+        }
+        if let Constraint::GreaterOrEqual | Constraint::Equal | Constraint::Range(_) = op {
+            constraints.push((lhs - rhs + width - (min + width) * &relaxed).ge(0));
+        }
+        Ok(name_split_rows(constraints, name))
+    }
+}
+
+/// Name the rows replacing a constraint: two rows are told apart by a `_le`/`_ge` suffix
+fn name_split_rows(rows: Vec<LpConstraint>, name: &Option<String>) -> Vec<LpConstraint> {
+    match name {
+        Some(n) if rows.len() == 2 => vec![
+            rows[0].with_name(&format!("{}_le", n)),
+            rows[1].with_name(&format!("{}_ge", n)),
+        ],
+        Some(n) => rows.iter().map(|row| row.with_name(n)).collect(),
+        None => rows,
+    }
+}
+
+/// Variable added column-wise: with its objective coefficient and its coefficients in
+/// existing constraints, referenced by their identifiers (see `LpProblem::constraint_id`).
+///
+/// # Examples
+///
+/// ```
+/// use lp_modeler::dsl::*;
+///
+/// let ref a = LpContinuous::new("a").lower_bound(0.0);
+///
+/// let mut problem = LpProblem::new("Columns", LpObjective::Minimize);
+/// problem += a;
+/// let demand = problem.add_constraint(a.ge(10));
+/// problem += a.le(20).with_name("capacity");
+///
+/// let ref b = LpContinuous::new("b").lower_bound(0.0);
+/// let column = LpColumn::new(b).with_objective(0.5).with_coefficient(&demand, 2.0);
+/// problem.add_column(column).unwrap();
+/// assert_eq!(problem.constraint(&demand), Some(&(a + 2 * b).ge(10)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LpColumn {
+    pub var: LpExpression,
+    pub objective: f64,
+    /// Identifiers of the constraints with the coefficient of the variable
+    pub coefficients: Vec<(String, f64)>,
+}
+
+impl LpColumn {
+    /// Column of `var`, absent from the objective and the constraints
+    pub fn new<T: Into<LpExpression>>(var: T) -> LpColumn {
+        LpColumn {
+            var: var.into(),
+            objective: 0.0,
+            coefficients: Vec::new(),
+        }
+    }
+
+    pub fn with_objective(&self, objective: f64) -> LpColumn {
+        LpColumn { objective, ..self.clone() }
+    }
+
+    /// Coefficient of the variable in the constraint identified by `id`
+    pub fn with_coefficient(&self, id: &str, coefficient: f64) -> LpColumn {
+        let mut coefficients = self.coefficients.clone();
+        coefficients.push((id.to_string(), coefficient));
+        LpColumn { coefficients, ..self.clone() }
+    }
+}
+
 pub trait Problem {
     fn add_objective_expression(&mut self, expr_arena: &mut LpExpression);
     fn add_constraints(&mut self, contraint_expr: &LpConstraint);
@@ -58,13 +255,20 @@ pub trait Problem {
 ///     Err(msg) => println!("{}", msg),
 /// }
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LpProblem {
     pub name: &'static str,
     pub unique_name: String,
     pub objective_type: LpObjective,
     pub obj_expr_arena: Option<LpExpression>,
+    /// Objectives of a problem with several objectives (see `LpMultiObjective`)
+    pub objectives: Vec<LpMultiObjective>,
     pub constraints: Vec<LpConstraint>,
+    pub sos: Vec<LpSos>,
+    pub indicators: Vec<LpIndicator>,
+    /// Number of removed constraints, added to the positions in the identifiers of the
+    /// unnamed constraints so that they are not reused
+    removed_constraints: usize,
 }
 
 impl LpProblem {
@@ -76,35 +280,389 @@ impl LpProblem {
             unique_name,
             objective_type: objective,
             obj_expr_arena: None,
+            objectives: Vec::new(),
             constraints: Vec::new(),
+            sos: Vec::new(),
+            indicators: Vec::new(),
+            removed_constraints: 0,
         }
     }
 
 
-    // TODO: Call once and pass into parameter
-    // TODO: Check variables on the objective function
-    pub fn variables(&self) -> HashMap<String, (usize, usize)> {
-        let mut lst: HashMap<String, (usize, usize)> = HashMap::new();
-        for constraint_index in 0..self.constraints.len() {
-            let constraint = self.constraints.get(constraint_index).unwrap();
-            constraint.var(constraint.0.get_root_index(), constraint_index, &mut lst);
+    /// Add a special ordered set
+    pub fn add_sos(&mut self, sos: LpSos) {
+        self.sos.push(sos);
+    }
+
+    /// Add an indicator constraint
+    pub fn add_indicator(&mut self, indicator: LpIndicator) {
+        self.indicators.push(indicator);
+    }
+
+    /// Identifier of the indicator constraint at `index`: the name of its constraint if it
+    /// has one, `ind{index + 1}` otherwise.
+    pub fn indicator_id(&self, index: usize) -> Option<String> {
+        self.indicators.get(index).map(|indicator| match indicator.constraint.name() {
+            Some(name) => name.to_string(),
+            None => format!("ind{}", index + 1),
+        })
+    }
+
+    /// Equivalent problem where the indicator constraints are replaced by big-M constraints,
+    /// for solvers that do not support them natively.
+    pub fn reformulate_indicators(&self) -> Result<LpProblem, String> {
+        let mut problem = self.clone();
+        problem.indicators.clear();
+        for indicator in &self.indicators {
+            problem.constraints.extend(indicator.to_big_m()?);
         }
-        lst
+        Ok(problem)
+    }
+
+    /// Equivalent problem where each range constraint is split into a `<=` and a `>=` row,
+    /// named after the identifier of the range with a `_le`/`_ge` suffix.
+    pub fn reformulate_ranges(&self) -> LpProblem {
+        let mut problem = self.clone();
+        problem.constraints = Vec::new();
+        for (index, constraint) in self.constraints.iter().enumerate() {
+            let LpConstraint(ref lhs, ref op, ref rhs, _) = *constraint;
+            if let Constraint::Range(width) = op {
+                let rows = vec![lhs.le(rhs), lhs.ge(rhs - *width)];
+                problem.constraints.extend(name_split_rows(rows, &self.constraint_id(index)));
+            } else {
+                problem.constraints.push(constraint.clone());
+            }
+        }
+        problem
+    }
+
+    /// Whether a constraint of the problem is a range
+    pub fn has_ranges(&self) -> bool {
+        self.constraints.iter().any(|constraint| matches!(constraint.1, Constraint::Range(_)))
+    }
+
+    /// Identifier of the constraint at `index`: its name if it has one, `c{index + 1}` otherwise
+    /// (shifted by the number of constraints removed with `remove_constraint`).
+    /// This is the row name written in the LP file.
+    pub fn constraint_id(&self, index: usize) -> Option<String> {
+        self.constraints.get(index).map(|constraint| match constraint.name() {
+            Some(name) => name.to_string(),
+            None => format!("c{}", index + 1 + self.removed_constraints),
+        })
+    }
+
+    /// Position of the constraint identified by `id` in `constraints`. Use `constraint_indices`
+    /// to look up many constraints.
+    pub fn constraint_index(&self, id: &str) -> Option<usize> {
+        // position of the unnamed constraint identified by `id`, if it has the form `c{n}`
+        let unnamed = id.strip_prefix('c')
+            .and_then(|n| n.parse::<usize>().ok())
+            .filter(|n| format!("c{}", n) == id);
+        (0..self.constraints.len()).find(|&index| match self.constraints[index].name() {
+            Some(name) => name == id,
+            None => unnamed == Some(index + 1 + self.removed_constraints),
+        })
+    }
+
+    /// Positions of the constraints by identifier. The first constraint is kept when several
+    /// share an identifier, as with `constraint_index`.
+    pub fn constraint_indices(&self) -> HashMap<String, usize> {
+        let mut indices = HashMap::with_capacity(self.constraints.len());
+        for index in 0..self.constraints.len() {
+            indices.entry(self.constraint_id(index).unwrap()).or_insert(index);
+        }
+        indices
+    }
+
+    /// Find a constraint by its identifier (see `constraint_id`)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    ///
+    /// let ref a = LpInteger::new("a");
+    ///
+    /// let mut problem = LpProblem::new("One Problem", LpObjective::Maximize);
+    /// problem += a.le(10);
+    /// problem += a.ge(2).with_name("at_least_two");
+    /// assert!(problem.constraint("c1").is_some());
+    /// assert!(problem.constraint("at_least_two").is_some());
+    /// ```
+    pub fn constraint(&self, id: &str) -> Option<&LpConstraint> {
+        self.constraint_index(id).and_then(|index| self.constraints.get(index))
+    }
+
+    /// Remove the constraint identified by `id` and return it.
+    ///
+    /// The other constraints keep their identifiers: the unnamed constraints are named after
+    /// their previous identifier, and the constraints added afterwards get new identifiers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    ///
+    /// let ref a = LpInteger::new("a");
+    ///
+    /// let mut problem = LpProblem::new("One Problem", LpObjective::Maximize);
+    /// problem += a;
+    /// problem += a.le(10);
+    /// problem += a.le(5);
+    /// assert!(problem.remove_constraint("c1").is_some());
+    /// assert!(problem.constraint("c2").is_some());
+    /// assert_eq!(problem.add_constraint(a.ge(1)), "c3");
+    /// ```
+    pub fn remove_constraint(&mut self, id: &str) -> Option<LpConstraint> {
+        let index = self.constraint_index(id)?;
+        for other in 0..self.constraints.len() {
+            if other != index && self.constraints[other].name().is_none() {
+                let other_id = self.constraint_id(other).unwrap();
+                self.constraints[other] = self.constraints[other].with_name(&other_id);
+            }
+        }
+        self.removed_constraints += 1;
+        Some(self.constraints.remove(index))
+    }
+
+    /// Replace the constraint identified by `id` and return the previous one.
+    /// An unnamed replacement keeps the identifier `id`.
+    pub fn replace_constraint(&mut self, id: &str, constraint: LpConstraint) -> Option<LpConstraint> {
+        let index = self.constraint_index(id)?;
+        let constraint = match constraint.name() {
+            Some(_) => constraint,
+            None => constraint.with_name(id),
+        };
+        Some(std::mem::replace(&mut self.constraints[index], constraint))
+    }
+
+    /// Change the right-hand side of the constraint identified by `id`, once its variables are
+    /// moved to the left-hand side. Use `set_range` for a range constraint.
+    pub fn set_rhs(&mut self, id: &str, rhs: f64) -> Result<(), String> {
+        let index = self.constraint_index(id).ok_or(format!("Unknown constraint {}", id))?;
+        let LpConstraint(lhs, op, _, name) = self.constraints[index].generalize();
+        if let Constraint::Range(_) = op {
+            return Err(format!("Constraint {} is a range: use set_range", id));
+        }
+        self.constraints[index] = LpConstraint(lhs, op, rhs.into(), name);
+        Ok(())
+    }
+
+    /// Change the bounds of the range constraint identified by `id`. `Err` if `lower` is
+    /// greater than `upper`.
+    pub fn set_range(&mut self, id: &str, lower: f64, upper: f64) -> Result<(), String> {
+        let index = self.constraint_index(id).ok_or(format!("Unknown constraint {}", id))?;
+        if lower > upper {
+            return Err(format!("The lower bound {} of range {} is greater than its upper bound {}", lower, id, upper));
+        }
+        let LpConstraint(lhs, op, _, name) = self.constraints[index].generalize();
+        if let Constraint::Range(_) = op {
+            self.constraints[index] = LpConstraint(lhs, Constraint::Range(upper - lower), upper.into(), name);
+            Ok(())
+        } else {
+            Err(format!("Constraint {} is not a range", id))
+        }
+    }
+
+    /// Add a constraint and return its identifier, a handle for `add_column` and the other
+    /// methods referencing constraints
+    pub fn add_constraint(&mut self, constraint: LpConstraint) -> String {
+        self.add_constraints(&constraint);
+        self.constraint_id(self.constraints.len() - 1).unwrap()
+    }
+
+    /// Add a variable to the objective and to existing constraints. The coefficients are added
+    /// to those of the variable if it is already there. Nothing is changed if a constraint is
+    /// unknown or if the variable is not a single variable.
+    pub fn add_column(&mut self, column: LpColumn) -> Result<(), String> {
+        let name = column.var.get_root_expr_ref().var_name()
+            .ok_or("A column must be a single variable")?
+            .to_string();
+        let constraint_indices = self.constraint_indices();
+        let indices = column.coefficients.iter()
+            .map(|(id, _)| constraint_indices.get(id).cloned().ok_or(format!("Unknown constraint {}", id)))
+            .collect::<Result<Vec<usize>, String>>()?;
+        let mut rows = Vec::with_capacity(indices.len());
+        for (&index, (_, coefficient)) in indices.iter().zip(&column.coefficients) {
+            let LpConstraint(lhs, op, rhs, constraint_name) = self.constraints[index].clone();
+            let mut lhs = lhs.to_linear()
+                .map_err(|_| format!("Cannot add {} to the quadratic constraint {}", name, self.constraint_id(index).unwrap()))?;
+            lhs.add_term(&column.var, *coefficient);
+            let lhs = LpExpression::from_linear_terms(lhs.var_terms(), lhs.constant());
+            rows.push((index, LpConstraint(lhs, op, rhs, constraint_name)));
+        }
+        if column.objective != 0.0 {
+            let current = match &self.obj_expr_arena {
+                Some(objective) => objective.to_linear()?.coefficient(&name),
+                None => 0.0,
+            };
+            self.set_objective_coefficient(&column.var, current + column.objective)?;
+        }
+        for (index, row) in rows {
+            self.constraints[index] = row;
+        }
+        Ok(())
+    }
+
+    /// Change the direction of the optimization
+    pub fn set_objective_type(&mut self, objective: LpObjective) {
+        self.objective_type = objective;
+    }
+
+    /// Change the coefficient of `var` in a linear objective, adding it if absent
+    pub fn set_objective_coefficient<T>(&mut self, var: T, coefficient: f64) -> Result<(), String>
+    where
+        T: Into<LpExpression>,
+    {
+        let var: LpExpression = var.into();
+        let name = var.get_root_expr_ref().var_name()
+            .ok_or("The objective coefficient can only be set for a variable")?
+            .to_string();
+        let mut objective = match &self.obj_expr_arena {
+            Some(objective) => objective.to_linear()?,
+            None => LpLinearExpression::new(),
+        };
+        let current = objective.coefficient(&name);
+        objective.add_term(var, coefficient - current);
+        self.obj_expr_arena = Some(LpExpression::from_linear_terms(objective.var_terms(), 0.0));
+        Ok(())
+    }
+
+    /// Change the bounds of the variable named `name` wherever it is used.
+    /// Binaries have no bounds: fix them with `fix_variable`.
+    pub fn set_bounds(&mut self, name: &str, lower_bound: Option<f64>, upper_bound: Option<f64>) -> Result<(), String> {
+        let declarations: Vec<&LpExprNode> = self.expressions().into_iter()
+            .flat_map(|expr| expr.reachable_nodes())
+            .filter(|node| node.var_name() == Some(name))
+            .collect();
+        if declarations.is_empty() {
+            return Err(format!("Unknown variable {}", name));
+        }
+        if declarations.iter().any(|node| node.with_bounds(None, None).is_none()) {
+            return Err(format!("Variable {} is a binary: use fix_variable", name));
+        }
+        let replace = |node: &LpExprNode| if node.var_name() == Some(name) {
+            node.with_bounds(lower_bound, upper_bound)
+        } else {
+            None
+        };
+        if let Some(objective) = self.obj_expr_arena.as_mut() {
+            objective.replace_nodes(replace);
+        }
+        for constraint in self.constraints.iter_mut().chain(self.indicators.iter_mut().map(|indicator| &mut indicator.constraint)) {
+            constraint.0.replace_nodes(replace);
+            constraint.2.replace_nodes(replace);
+        }
+        Ok(())
+    }
+
+    /// Fix the variable named `name` to `value`. A binary is fixed by a constraint named
+    /// `{name}_fixed`, replaced if the binary is fixed again.
+    pub fn fix_variable(&mut self, name: &str, value: f64) -> Result<(), String> {
+        match self.set_bounds(name, Some(value), Some(value)) {
+            Err(_) if self.is_binary(name) => {
+                let fixed = LpBinary::new(name).equal(value).with_name(&format!("{}_fixed", name));
+                let id = format!("{}_fixed", name);
+                if self.replace_constraint(&id, fixed.clone()).is_none() {
+                    self.constraints.push(fixed);
+                }
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    fn is_binary(&self, name: &str) -> bool {
+        self.expressions().into_iter()
+            .flat_map(|expr| expr.reachable_nodes())
+            .any(|node| matches!(node, LpExprNode::ConsBin(LpBinary { name: n }) if n == name))
+    }
+
+    /// Objective and both sides of the constraints, including those of the indicators
+    fn expressions(&self) -> Vec<&LpExpression> {
+        let mut expressions: Vec<&LpExpression> = self.obj_expr_arena.iter().collect();
+        for constraint in self.constraints.iter().chain(self.indicators.iter().map(|indicator| &indicator.constraint)) {
+            expressions.push(&constraint.0);
+            expressions.push(&constraint.2);
+        }
+        expressions
+    }
+
+    /// Whether the objective has products of variables
+    pub fn has_quadratic_objective(&self) -> bool {
+        self.obj_expr_arena.as_ref().is_some_and(|expr| !expr.is_linear())
+    }
+
+    /// Whether a constraint (possibly of an indicator) has products of variables
+    pub fn has_quadratic_constraints(&self) -> bool {
+        self.constraints.iter()
+            .chain(self.indicators.iter().map(|indicator| &indicator.constraint))
+            .any(|constraint| !constraint.0.is_linear() || !constraint.2.is_linear())
+    }
+
+    /// Whether the problem uses semi-continuous or semi-integer variables
+    pub fn has_semi_continuous(&self) -> bool {
+        self.variables().iter().any(|var| var.is_semi_continuous())
+    }
+
+    /// Equivalent problem without semi-continuous or semi-integer variables, for solvers
+    /// that do not support them natively.
+    ///
+    /// Each semi-continuous (resp. semi-integer) variable `x` with bounds `[l, u]` becomes a
+    /// continuous (resp. integer) variable linked to a new binary `x_on` with
+    /// `l * x_on <= x <= u * x_on`. The upper bound is required.
+    pub fn reformulate_semi_continuous(&self) -> Result<LpProblem, String> {
+        let mut replacements: HashMap<String, LpExprNode> = HashMap::new();
+        let mut linking_constraints: Vec<LpConstraint> = Vec::new();
+        for var in &self.variables() {
+            let name = var.name().to_string();
+            let replacement = match var {
+                LpVariable::SemiContinuous(_) => LpExprNode::ConsCont(LpContinuous::new(&name).lower_bound(0.0)),
+                LpVariable::SemiInteger(_) => LpExprNode::ConsInt(LpInteger::new(&name).lower_bound(0.0)),
+                _ => continue,
+            };
+            let bounds = var.bounds();
+            let upper = bounds.1.ok_or(format!("Semi-continuous variable {} needs an upper bound", name))?;
+            let lower = bounds.0.unwrap_or(0.0);
+            let on = &LpBinary::new(&format!("{}_on", name));
+            let var: LpExpression = replacement.clone().into();
+            linking_constraints.push((&var - upper * on).le(0));
+            if lower != 0.0 {
+                linking_constraints.push((&var - lower * on).ge(0));
+            }
+            replacements.insert(name, replacement);
+        }
+
+        let replace = |expr: &LpExprNode| match expr {
+            LpExprNode::ConsSemiCont(LpSemiContinuous { name, .. })
+            | LpExprNode::ConsSemiInt(LpSemiInteger { name, .. }) => replacements.get(name).cloned(),
+            _ => None,
+        };
+        let mut problem = self.clone();
+        if let Some(objective) = problem.obj_expr_arena.as_mut() {
+            objective.replace_nodes(replace);
+        }
+        for constraint in problem.constraints.iter_mut() {
+            constraint.0.replace_nodes(replace);
+        }
+        problem.constraints.extend(linking_constraints);
+        Ok(problem)
     }
 }
 
 impl Problem for LpProblem {
     fn add_objective_expression(&mut self, expr_arena: &mut LpExpression) {
-        if let Some(e) = &self.obj_expr_arena {
-            let mut simple_expr = expr_arena
-                .merge_cloned_arenas(&e, LpExprOp::Addition);
-            let _ = simple_expr.simplify().split_off_constant();
-            self.obj_expr_arena = Some(simple_expr);
+        let mut simple_expr = if let Some(e) = &self.obj_expr_arena {
+            expr_arena.merge_cloned_arenas(&e, LpExprOp::Addition)
+        } else {
+            expr_arena.clone()
+        };
+        if let Ok((terms, _)) = simple_expr.linear_terms() {
+            simple_expr = LpExpression::from_linear_terms(&terms, 0.0);
         } else {
-            let mut simple_expr = expr_arena.clone();
             let _ = simple_expr.simplify().split_off_constant();
-            self.obj_expr_arena = Some(simple_expr);
         }
+        self.obj_expr_arena = Some(simple_expr);
     }
 
     fn add_constraints(&mut self, constraint_expr: &LpConstraint) {
@@ -120,6 +678,24 @@ macro_rules! impl_addassign_for_generic_problem {
                 self.add_constraints(&_rhs);
             }
         }
+        /// Add a special ordered set
+        impl AddAssign<LpSos> for $problem {
+            fn add_assign(&mut self, _rhs: LpSos) {
+                self.add_sos(_rhs);
+            }
+        }
+        /// Add an indicator constraint
+        impl AddAssign<LpIndicator> for $problem {
+            fn add_assign(&mut self, _rhs: LpIndicator) {
+                self.add_indicator(_rhs);
+            }
+        }
+        /// Add an objective to a problem with several objectives
+        impl AddAssign<LpMultiObjective> for $problem {
+            fn add_assign(&mut self, _rhs: LpMultiObjective) {
+                self.add_objective(_rhs);
+            }
+        }
         /// Add an expression as an objective function
         impl<T> AddAssign<T> for $problem
         where
@@ -0,0 +1,155 @@
+//! Registry of the variables of a problem with their types and bounds.
+use std::collections::HashMap;
+
+use dsl::{LpBinary, LpContinuous, LpExprNode, LpExpression, LpInteger, LpProblem, LpSemiContinuous, LpSemiInteger};
+
+/// Variable of a problem with its type and bounds
+#[derive(Debug, Clone, PartialEq)]
+pub enum LpVariable {
+    Binary(LpBinary),
+    Integer(LpInteger),
+    Continuous(LpContinuous),
+    SemiContinuous(LpSemiContinuous),
+    SemiInteger(LpSemiInteger),
+}
+
+impl LpVariable {
+    pub(crate) fn from_node(node: &LpExprNode) -> Option<LpVariable> {
+        match node {
+            LpExprNode::ConsBin(var) => Some(LpVariable::Binary(var.clone())),
+            LpExprNode::ConsInt(var) => Some(LpVariable::Integer(var.clone())),
+            LpExprNode::ConsCont(var) => Some(LpVariable::Continuous(var.clone())),
+            LpExprNode::ConsSemiCont(var) => Some(LpVariable::SemiContinuous(var.clone())),
+            LpExprNode::ConsSemiInt(var) => Some(LpVariable::SemiInteger(var.clone())),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            LpVariable::Binary(LpBinary { name })
+            | LpVariable::Integer(LpInteger { name, .. })
+            | LpVariable::Continuous(LpContinuous { name, .. })
+            | LpVariable::SemiContinuous(LpSemiContinuous { name, .. })
+            | LpVariable::SemiInteger(LpSemiInteger { name, .. }) => name,
+        }
+    }
+
+    /// Declared lower and upper bounds (none for a binary)
+    pub fn bounds(&self) -> (Option<f64>, Option<f64>) {
+        match self {
+            LpVariable::Binary(_) => (None, None),
+            LpVariable::Integer(LpInteger { lower_bound, upper_bound, .. })
+            | LpVariable::Continuous(LpContinuous { lower_bound, upper_bound, .. })
+            | LpVariable::SemiContinuous(LpSemiContinuous { lower_bound, upper_bound, .. })
+            | LpVariable::SemiInteger(LpSemiInteger { lower_bound, upper_bound, .. }) => (*lower_bound, *upper_bound),
+        }
+    }
+
+    /// Whether the variable takes integer values (binaries, integers and semi-integers)
+    pub fn is_integer(&self) -> bool {
+        matches!(self, LpVariable::Binary(_) | LpVariable::Integer(_) | LpVariable::SemiInteger(_))
+    }
+
+    /// Whether the variable can be either 0 or within its bounds
+    pub fn is_semi_continuous(&self) -> bool {
+        matches!(self, LpVariable::SemiContinuous(_) | LpVariable::SemiInteger(_))
+    }
+}
+
+impl From<LpVariable> for LpExpression {
+    fn from(var: LpVariable) -> Self {
+        match var {
+            LpVariable::Binary(var) => var.into(),
+            LpVariable::Integer(var) => var.into(),
+            LpVariable::Continuous(var) => var.into(),
+            LpVariable::SemiContinuous(var) => var.into(),
+            LpVariable::SemiInteger(var) => var.into(),
+        }
+    }
+}
+
+impl<'a> From<&'a LpVariable> for LpExpression {
+    fn from(var: &'a LpVariable) -> Self {
+        var.clone().into()
+    }
+}
+
+/// Variables of a problem, in the order they first appear in the objectives, the
+/// constraints and the indicators. A variable declared several times keeps its first
+/// declaration (see `LpProblem::validate` to detect conflicting declarations).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LpVariables {
+    variables: Vec<LpVariable>,
+    positions: HashMap<String, usize>,
+}
+
+impl LpVariables {
+    fn register(&mut self, node: &LpExprNode) {
+        if let Some(var) = LpVariable::from_node(node) {
+            if !self.positions.contains_key(var.name()) {
+                self.positions.insert(var.name().to_string(), self.variables.len());
+                self.variables.push(var);
+            }
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LpVariable> {
+        self.positions.get(name).map(|&position| &self.variables[position])
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.positions.contains_key(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &LpVariable> {
+        self.variables.iter()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.variables.iter().map(|var| var.name())
+    }
+
+    /// Number of variables
+    pub fn len(&self) -> usize {
+        self.variables.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.variables.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for &'a LpVariables {
+    type Item = &'a LpVariable;
+    type IntoIter = ::std::slice::Iter<'a, LpVariable>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.variables.iter()
+    }
+}
+
+impl LpProblem {
+    /// Registry of every variable of the problem, including those only used in an objective
+    /// or by an indicator, with their types and bounds. It is built by walking every
+    /// expression of the problem: keep it rather than calling this method repeatedly.
+    pub fn variables(&self) -> LpVariables {
+        let mut variables = LpVariables::default();
+        let objectives = self.obj_expr_arena.iter()
+            .chain(self.objectives.iter().map(|objective| &objective.expr));
+        for expr in objectives {
+            expr.reachable_nodes().into_iter().for_each(|node| variables.register(node));
+        }
+        for constraint in &self.constraints {
+            for expr in &[&constraint.0, &constraint.2] {
+                expr.reachable_nodes().into_iter().for_each(|node| variables.register(node));
+            }
+        }
+        for indicator in &self.indicators {
+            variables.register(&LpExprNode::ConsBin(indicator.binary.clone()));
+            for expr in &[&indicator.constraint.0, &indicator.constraint.2] {
+                expr.reachable_nodes().into_iter().for_each(|node| variables.register(node));
+            }
+        }
+        variables
+    }
+}
@@ -0,0 +1,268 @@
+//! Checks of a problem for modeling mistakes, before it reaches a solver.
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::mem::discriminant;
+
+use dsl::{Constraint, LpConstraint, LpExprNode, LpExpression, LpProblem};
+use util::is_zero;
+
+/// Mistake found by `LpProblem::validate`
+#[derive(Debug, Clone, PartialEq)]
+pub enum LpValidationIssue {
+    /// Variables of different types share a name
+    ConflictingTypes { name: String },
+    /// The same variable is used with different bounds
+    ConflictingBounds { name: String },
+    /// The lower bound of a variable is greater than its upper bound
+    EmptyDomain { name: String, lower_bound: f64, upper_bound: f64 },
+    /// The lower bound of a range constraint is greater than its upper bound
+    EmptyRange { constraint: String, width: f64 },
+    /// A NaN or infinite number in a constraint, the objective, a bound or a set
+    InvalidNumber { location: String },
+    /// A constraint without variable which can never be satisfied
+    InfeasibleConstraint { constraint: String },
+    /// A constraint without variable which always holds
+    TrivialConstraint { constraint: String },
+    /// Several rows of the LP file would share the same name
+    DuplicateConstraintName { name: String },
+    /// An expression which cannot be given to a solver, e.g. of degree higher than two
+    UnsupportedExpression { location: String, reason: String },
+}
+
+impl LpValidationIssue {
+    /// Whether the issue prevents solving the problem as intended. Other issues are warnings.
+    pub fn is_error(&self) -> bool {
+        !matches!(self, LpValidationIssue::TrivialConstraint { .. })
+    }
+}
+
+impl fmt::Display for LpValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::LpValidationIssue::*;
+        match self {
+            ConflictingTypes { name } => write!(f, "variable {} is declared with different types", name),
+            ConflictingBounds { name } => write!(f, "variable {} is declared with different bounds", name),
+            EmptyDomain { name, lower_bound, upper_bound } => write!(
+                f,
+                "variable {} has a lower bound {} greater than its upper bound {}",
+                name, lower_bound, upper_bound
+            ),
+            EmptyRange { constraint, width } => write!(
+                f,
+                "range constraint {} has a lower bound greater than its upper bound (width {})",
+                constraint, width
+            ),
+            InvalidNumber { location } => write!(f, "{} contains a NaN or infinite number", location),
+            InfeasibleConstraint { constraint } => write!(f, "constraint {} has no variable and is never satisfied", constraint),
+            TrivialConstraint { constraint } => write!(f, "constraint {} has no variable and always holds", constraint),
+            DuplicateConstraintName { name } => write!(f, "several constraints are named {}", name),
+            UnsupportedExpression { location, reason } => write!(f, "{}: {}", location, reason),
+        }
+    }
+}
+
+/// Result of `LpProblem::validate`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LpValidation {
+    pub errors: Vec<LpValidationIssue>,
+    pub warnings: Vec<LpValidationIssue>,
+}
+
+impl LpValidation {
+    fn push(&mut self, issue: LpValidationIssue) {
+        if issue.is_error() {
+            self.errors.push(issue);
+        } else {
+            self.warnings.push(issue);
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// `Err` listing the errors, if any
+    pub fn check(&self) -> Result<(), String> {
+        if self.is_valid() {
+            Ok(())
+        } else {
+            let errors: Vec<String> = self.errors.iter().map(|e| e.to_string()).collect();
+            Err(format!("Invalid problem: {}", errors.join("; ")))
+        }
+    }
+}
+
+/// Bounds as declared on a variable node
+fn declared_bounds(node: &LpExprNode) -> (Option<f64>, Option<f64>) {
+    match node {
+        LpExprNode::ConsInt(v) => (v.lower_bound, v.upper_bound),
+        LpExprNode::ConsCont(v) => (v.lower_bound, v.upper_bound),
+        LpExprNode::ConsSemiCont(v) => (v.lower_bound, v.upper_bound),
+        LpExprNode::ConsSemiInt(v) => (v.lower_bound, v.upper_bound),
+        _ => (None, None),
+    }
+}
+
+/// Collects the declarations of the variables met in the expressions of a problem
+#[derive(Default)]
+struct VariableCheck {
+    declarations: HashMap<String, LpExprNode>,
+    reported: HashSet<String>,
+}
+
+impl VariableCheck {
+    fn check_expression(&mut self, expr: &LpExpression, location: &str, validation: &mut LpValidation) {
+        let mut invalid_number = false;
+        for node in expr.reachable_nodes() {
+            match node {
+                LpExprNode::LitVal(value) => invalid_number |= !value.is_finite(),
+                LpExprNode::LpCompExpr(..) | LpExprNode::EmptyExpr => {}
+                var => self.check_variable(var, validation),
+            }
+        }
+        if invalid_number {
+            validation.push(LpValidationIssue::InvalidNumber { location: location.to_string() });
+        }
+    }
+
+    fn check_variable(&mut self, var: &LpExprNode, validation: &mut LpValidation) {
+        let name = var.var_name().unwrap();
+        match self.declarations.get(name) {
+            None => {
+                self.declarations.insert(name.to_string(), var.clone());
+                let bounds = declared_bounds(var);
+                if let (Some(lower_bound), Some(upper_bound)) = bounds {
+                    if lower_bound > upper_bound {
+                        validation.push(LpValidationIssue::EmptyDomain { name: name.to_string(), lower_bound, upper_bound });
+                    }
+                }
+                if [bounds.0, bounds.1].iter().flatten().any(|bound| bound.is_nan()) {
+                    validation.push(LpValidationIssue::InvalidNumber { location: format!("bounds of {}", name) });
+                }
+            }
+            Some(declared) if declared != var && !self.reported.contains(name) => {
+                self.reported.insert(name.to_string());
+                if discriminant(declared) != discriminant(var) {
+                    validation.push(LpValidationIssue::ConflictingTypes { name: name.to_string() });
+                } else {
+                    validation.push(LpValidationIssue::ConflictingBounds { name: name.to_string() });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Issue of the width of a range constraint, if any
+fn check_range_width(constraint: &LpConstraint, id: &str, validation: &mut LpValidation) {
+    if let Constraint::Range(width) = constraint.1 {
+        if !width.is_finite() {
+            validation.push(LpValidationIssue::InvalidNumber { location: format!("constraint {}", id) });
+        } else if width < 0.0 {
+            validation.push(LpValidationIssue::EmptyRange { constraint: id.to_string(), width });
+        }
+    }
+}
+
+/// Whether `constant op 0` holds
+fn constant_holds(constant: f64, op: &Constraint) -> bool {
+    match op {
+        Constraint::LessOrEqual => constant <= 0.0 || is_zero(constant),
+        Constraint::GreaterOrEqual => constant >= 0.0 || is_zero(constant),
+        Constraint::Equal => is_zero(constant),
+        Constraint::Range(width) => (constant >= -width || is_zero(constant + width)) && (constant <= 0.0 || is_zero(constant)),
+    }
+}
+
+fn check_constant_constraint(constraint: &LpConstraint, id: &str, validation: &mut LpValidation) {
+    let LpConstraint(ref lhs, ref op, ref rhs, _) = *constraint;
+    // both sides are decomposed on their own as the left one is empty once all its terms cancel out
+    match (lhs.quadratic_terms(), rhs.quadratic_terms()) {
+        (Err(reason), _) | (_, Err(reason)) => validation.push(LpValidationIssue::UnsupportedExpression {
+            location: format!("constraint {}", id),
+            reason,
+        }),
+        (Ok(lhs_terms), Ok(rhs_terms)) => {
+            let has_variables = [&lhs_terms, &rhs_terms].iter().any(|terms| {
+                !terms.quadratic.is_empty() || terms.linear.iter().any(|&(_, c)| c != 0.0)
+            });
+            let constant = lhs_terms.constant - rhs_terms.constant;
+            if !has_variables && constant.is_finite() {
+                let constraint = id.to_string();
+                if constant_holds(constant, op) {
+                    validation.push(LpValidationIssue::TrivialConstraint { constraint });
+                } else {
+                    validation.push(LpValidationIssue::InfeasibleConstraint { constraint });
+                }
+            }
+        }
+    }
+}
+
+impl LpProblem {
+    /// Look for modeling mistakes: variables sharing a name with different types or bounds,
+    /// empty domains and ranges, NaN or infinite numbers, constraints without variables, duplicate
+    /// constraint names and expressions of degree higher than two.
+    ///
+    /// Solvers run it before solving when enabled with `WithValidation::with_validation`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    ///
+    /// let ref x = LpContinuous::new("x").lower_bound(5.0).upper_bound(1.0);
+    /// let ref y = LpInteger::new("x");
+    ///
+    /// let mut problem = LpProblem::new("Mistakes", LpObjective::Maximize);
+    /// problem += x + y;
+    /// problem += (x + y).le(10);
+    ///
+    /// let validation = problem.validate();
+    /// assert_eq!(validation.errors.len(), 2);
+    /// assert!(validation.check().is_err());
+    /// ```
+    pub fn validate(&self) -> LpValidation {
+        let mut validation = LpValidation::default();
+        let mut variables = VariableCheck::default();
+        let objectives = self.obj_expr_arena.iter()
+            .map(|objective| ("objective".to_string(), objective))
+            .chain(self.objectives.iter().map(|objective| (format!("objective {}", objective.name), &objective.expr)));
+        for (location, objective) in objectives {
+            variables.check_expression(objective, &location, &mut validation);
+            if let Err(reason) = objective.quadratic_terms() {
+                validation.push(LpValidationIssue::UnsupportedExpression { location, reason });
+            }
+        }
+        let mut names: HashMap<String, usize> = HashMap::new();
+        for (index, constraint) in self.constraints.iter().enumerate() {
+            let id = self.constraint_id(index).unwrap();
+            let location = format!("constraint {}", id);
+            variables.check_expression(&constraint.0, &location, &mut validation);
+            variables.check_expression(&constraint.2, &location, &mut validation);
+            check_range_width(constraint, &id, &mut validation);
+            check_constant_constraint(constraint, &id, &mut validation);
+            *names.entry(id).or_insert(0) += 1;
+        }
+        for (index, indicator) in self.indicators.iter().enumerate() {
+            let id = self.indicator_id(index).unwrap();
+            variables.check_variable(&LpExprNode::ConsBin(indicator.binary.clone()), &mut validation);
+            let location = format!("constraint {}", id);
+            variables.check_expression(&indicator.constraint.0, &location, &mut validation);
+            variables.check_expression(&indicator.constraint.2, &location, &mut validation);
+            check_range_width(&indicator.constraint, &id, &mut validation);
+            *names.entry(id).or_insert(0) += 1;
+        }
+        for sos in &self.sos {
+            if sos.weighted_vars.iter().any(|(_, weight)| !weight.is_finite()) {
+                validation.push(LpValidationIssue::InvalidNumber { location: format!("set {}", sos.name) });
+            }
+        }
+        let mut duplicates: Vec<String> = names.into_iter().filter(|&(_, count)| count > 1).map(|(name, _)| name).collect();
+        duplicates.sort();
+        for name in duplicates {
+            validation.push(LpValidationIssue::DuplicateConstraintName { name });
+        }
+        validation
+    }
+}
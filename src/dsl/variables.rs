@@ -10,9 +10,11 @@ use std::convert::Into;
 use std::collections::HashMap;
 use std::fmt::Write;
 
+/// Variables with bounds. The bounds are stored as `f64` but can be given as any type
+/// converting into it (e.g. `f32` or `i32`).
 pub trait BoundableLp: PartialEq + Clone {
-    fn lower_bound(&self, lw: f32) -> Self;
-    fn upper_bound(&self, up: f32) -> Self;
+    fn lower_bound<N: Into<f64>>(&self, lw: N) -> Self;
+    fn upper_bound<N: Into<f64>>(&self, up: N) -> Self;
 }
 
 // A binary variable is constrained to be either 1 or 0. Refer to the
@@ -44,8 +46,8 @@ impl ToTokens for LpBinary {
 #[derive(Debug, Clone, PartialEq)]
 pub struct LpInteger {
     pub(crate) name: String,
-    pub(crate) lower_bound: Option<f32>,
-    pub(crate) upper_bound: Option<f32>,
+    pub(crate) lower_bound: Option<f64>,
+    pub(crate) upper_bound: Option<f64>,
 }
 impl LpInteger {
     pub fn new(name: &str) -> LpInteger {
@@ -80,8 +82,8 @@ impl ToTokens for LpInteger {
 #[derive(Debug, Clone, PartialEq)]
 pub struct LpContinuous {
     pub name: String,
-    pub lower_bound: Option<f32>,
-    pub upper_bound: Option<f32>,
+    pub lower_bound: Option<f64>,
+    pub upper_bound: Option<f64>,
 }
 impl LpContinuous {
     pub fn new(name: &str) -> LpContinuous {
@@ -113,21 +115,98 @@ impl ToTokens for LpContinuous {
     }
 }
 
+/// A semi-continuous variable is either 0 or between its lower and upper bounds.
+/// The upper bound is required by most solvers. Refer to the
+/// [LP format documentation](https://www.gurobi.com/documentation/9.0/refman/variables.html)
+/// for details.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LpSemiContinuous {
+    pub name: String,
+    pub lower_bound: Option<f64>,
+    pub upper_bound: Option<f64>,
+}
+impl LpSemiContinuous {
+    pub fn new(name: &str) -> LpSemiContinuous {
+        LpSemiContinuous {
+            name: name.to_string(),
+            lower_bound: None,
+            upper_bound: None,
+        }
+    }
+}
+impl ToTokens for LpSemiContinuous {
+    fn to_tokens(&self, stream: &mut TokenStream) {
+        let name = &self.name;
+        let lower_bound = match self.lower_bound {
+            Some(ref v) => quote!(Some(#v)),
+            None => quote!(None)
+        };
+        let upper_bound = match self.upper_bound {
+            Some(ref v) => quote!(Some(#v)),
+            None => quote!(None)
+        };
+        stream.extend(quote! {
+            LpSemiContinuous{
+                name: #name.to_string(),
+                lower_bound: #lower_bound,
+                upper_bound: #upper_bound
+            }
+        });
+    }
+}
+
+/// A semi-integer variable is either 0 or an integer between its lower and upper bounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LpSemiInteger {
+    pub name: String,
+    pub lower_bound: Option<f64>,
+    pub upper_bound: Option<f64>,
+}
+impl LpSemiInteger {
+    pub fn new(name: &str) -> LpSemiInteger {
+        LpSemiInteger {
+            name: name.to_string(),
+            lower_bound: None,
+            upper_bound: None,
+        }
+    }
+}
+impl ToTokens for LpSemiInteger {
+    fn to_tokens(&self, stream: &mut TokenStream) {
+        let name = &self.name;
+        let lower_bound = match self.lower_bound {
+            Some(ref v) => quote!(Some(#v)),
+            None => quote!(None)
+        };
+        let upper_bound = match self.upper_bound {
+            Some(ref v) => quote!(Some(#v)),
+            None => quote!(None)
+        };
+        stream.extend(quote! {
+            LpSemiInteger{
+                name: #name.to_string(),
+                lower_bound: #lower_bound,
+                upper_bound: #upper_bound
+            }
+        });
+    }
+}
+
 macro_rules! implement_boundable {
     ($lp_type: ident) => {
         impl BoundableLp for $lp_type {
-            fn lower_bound(&self, lw: f32) -> $lp_type {
+            fn lower_bound<N: Into<f64>>(&self, lw: N) -> $lp_type {
                 $lp_type {
                     name: self.name.clone(),
-                    lower_bound: Some(lw),
+                    lower_bound: Some(lw.into()),
                     upper_bound: self.upper_bound,
                 }
             }
-            fn upper_bound(&self, up: f32) -> $lp_type {
+            fn upper_bound<N: Into<f64>>(&self, up: N) -> $lp_type {
                 $lp_type {
                     name: self.name.clone(),
                     lower_bound: self.lower_bound,
-                    upper_bound: Some(up),
+                    upper_bound: Some(up.into()),
                 }
             }
         }
@@ -135,6 +214,8 @@ macro_rules! implement_boundable {
 }
 implement_boundable!(LpInteger);
 implement_boundable!(LpContinuous);
+implement_boundable!(LpSemiContinuous);
+implement_boundable!(LpSemiInteger);
 
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) enum LpExprOp {
@@ -170,11 +251,53 @@ pub(crate) enum LpExprNode {
     ConsInt(LpInteger),
     ConsBin(LpBinary),
     ConsCont(LpContinuous),
-    LitVal(f32),
+    ConsSemiCont(LpSemiContinuous),
+    ConsSemiInt(LpSemiInteger),
+    LitVal(f64),
     EmptyExpr,
     LpCompExpr(LpExprOp, LpExprArenaIndex, LpExprArenaIndex)
 }
 
+impl LpExprNode {
+    /// Name of the variable if the node is a variable
+    pub(crate) fn var_name(&self) -> Option<&str> {
+        match self {
+            ConsBin(LpBinary { name })
+            | ConsInt(LpInteger { name, .. })
+            | ConsCont(LpContinuous { name, .. })
+            | ConsSemiCont(LpSemiContinuous { name, .. })
+            | ConsSemiInt(LpSemiInteger { name, .. }) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Bounds of the variable if the node is a variable (`None` if unbounded)
+    pub(crate) fn var_bounds(&self) -> Option<(Option<f64>, Option<f64>)> {
+        match self {
+            ConsBin(_) => Some((Some(0.0), Some(1.0))),
+            ConsInt(LpInteger { lower_bound, upper_bound, .. })
+            | ConsCont(LpContinuous { lower_bound, upper_bound, .. }) => Some((*lower_bound, *upper_bound)),
+            ConsSemiCont(LpSemiContinuous { lower_bound, upper_bound, .. })
+            | ConsSemiInt(LpSemiInteger { lower_bound, upper_bound, .. }) => Some((
+                Some(lower_bound.unwrap_or(0.0).min(0.0)),
+                upper_bound.map(|u| u.max(0.0)),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Same variable with other bounds, or `None` if the node is not a variable with bounds
+    pub(crate) fn with_bounds(&self, lower_bound: Option<f64>, upper_bound: Option<f64>) -> Option<LpExprNode> {
+        match self {
+            ConsInt(v) => Some(ConsInt(LpInteger { lower_bound, upper_bound, ..v.clone() })),
+            ConsCont(v) => Some(ConsCont(LpContinuous { lower_bound, upper_bound, ..v.clone() })),
+            ConsSemiCont(v) => Some(ConsSemiCont(LpSemiContinuous { lower_bound, upper_bound, ..v.clone() })),
+            ConsSemiInt(v) => Some(ConsSemiInt(LpSemiInteger { lower_bound, upper_bound, ..v.clone() })),
+            _ => None,
+        }
+    }
+}
+
 impl ToTokens for LpExprNode {
     fn to_tokens(&self, stream: &mut TokenStream) {
         stream.extend(
@@ -182,6 +305,8 @@ impl ToTokens for LpExprNode {
                 LpExprNode::ConsInt(v) => quote!(LpExprNode::ConsInt(#v)),
                 LpExprNode::ConsBin(v) => quote!(LpExprNode::ConsBin(#v)),
                 LpExprNode::ConsCont(v) => quote!(LpExprNode::ConsCont(#v)),
+                LpExprNode::ConsSemiCont(v) => quote!(LpExprNode::ConsSemiCont(#v)),
+                LpExprNode::ConsSemiInt(v) => quote!(LpExprNode::ConsSemiInt(#v)),
                 LpExprNode::LpCompExpr(op, lhs, rhs) => quote!(LpExprNode::LpCompExpr(#op, #lhs, #rhs)),
                 LpExprNode::LitVal(v) =>  quote!(LpExprNode::LitVal(#v)),
                 LpExprNode::EmptyExpr => quote!(LpExprNode::EmptyExpr),
@@ -208,22 +333,24 @@ macro_rules! cons_into_expr {
 cons_into_expr!(LpBinary, ConsBin);
 cons_into_expr!(LpInteger, ConsInt);
 cons_into_expr!(LpContinuous, ConsCont);
+cons_into_expr!(LpSemiContinuous, ConsSemiCont);
+cons_into_expr!(LpSemiInteger, ConsSemiInt);
 
 macro_rules! lit_into_expr {
     ($type_from:ty) => {
         impl From<$type_from> for LpExprNode {
             fn from(from: $type_from) -> Self {
-                    LitVal(from as f32)
+                    LitVal(from as f64)
             }
         }
         impl<'a> From<&'a $type_from> for LpExprNode {
             fn from(from: &'a $type_from) -> Self {
-                    LitVal((*from).clone() as f32)
+                    LitVal((*from).clone() as f64)
             }
         }
     };
 }
-lit_into_expr!(f32);
+lit_into_expr!(f64);
 lit_into_expr!(i32);
 
 #[derive(Debug, Clone, PartialEq)]
@@ -269,6 +396,8 @@ macro_rules! cons_into_expr_arena {
 cons_into_expr_arena!(LpBinary, ConsBin);
 cons_into_expr_arena!(LpInteger, ConsInt);
 cons_into_expr_arena!(LpContinuous, ConsCont);
+cons_into_expr_arena!(LpSemiContinuous, ConsSemiCont);
+cons_into_expr_arena!(LpSemiInteger, ConsSemiInt);
 
 macro_rules! lit_into_expr_arena {
     ($type_from:ty) => {
@@ -276,7 +405,7 @@ macro_rules! lit_into_expr_arena {
             fn from(from: $type_from) -> Self {
                 LpExpression {
                     root: 0,
-                    arena: vec![LitVal(from as f32); 1]
+                    arena: vec![LitVal(from as f64); 1]
                 }
             }
         }
@@ -284,13 +413,13 @@ macro_rules! lit_into_expr_arena {
             fn from(from: &'a $type_from) -> Self {
                 LpExpression {
                     root: 0,
-                    arena: vec![LitVal((*from).clone() as f32); 1]
+                    arena: vec![LitVal((*from).clone() as f64); 1]
                 }
             }
         }
     };
 }
-lit_into_expr_arena!(f32);
+lit_into_expr_arena!(f64);
 lit_into_expr_arena!(i32);
 
 impl From<LpExprNode> for LpExpression {
@@ -311,15 +440,110 @@ impl From<&LpExprNode> for LpExpression {
     }
 }
 
+/// Terms of an expression of degree at most two, merged by variable names
+#[derive(Debug, Clone, Default)]
+pub(crate) struct QuadraticTerms {
+    /// Products of two variables, ordered by name, with their coefficient
+    pub(crate) quadratic: Vec<(LpExprNode, LpExprNode, f64)>,
+    pub(crate) linear: Vec<(LpExprNode, f64)>,
+    pub(crate) constant: f64,
+    /// Positions of the variables by name. Variables declared differently under the same name
+    /// are kept apart for `LpProblem::validate` to report them.
+    linear_positions: HashMap<String, Vec<usize>>,
+    quadratic_positions: HashMap<(String, String), usize>,
+}
+
+impl QuadraticTerms {
+    fn degree(&self) -> u8 {
+        if !self.quadratic.is_empty() {
+            2
+        } else if !self.linear.is_empty() {
+            1
+        } else {
+            0
+        }
+    }
+
+    fn add_linear(&mut self, var: &LpExprNode, coefficient: f64) {
+        let name = var.var_name().unwrap().to_string();
+        let linear = &mut self.linear;
+        let positions = self.linear_positions.entry(name).or_default();
+        match positions.iter().find(|&&position| linear[position].0 == *var) {
+            Some(&position) => linear[position].1 += coefficient,
+            None => {
+                positions.push(linear.len());
+                linear.push((var.clone(), coefficient));
+            }
+        }
+    }
+
+    fn add_quadratic(&mut self, a: &LpExprNode, b: &LpExprNode, coefficient: f64) {
+        let (a, b) = if a.var_name() <= b.var_name() { (a, b) } else { (b, a) };
+        let key = (a.var_name().unwrap().to_string(), b.var_name().unwrap().to_string());
+        match self.quadratic_positions.get(&key) {
+            Some(&position) => self.quadratic[position].2 += coefficient,
+            None => {
+                self.quadratic_positions.insert(key, self.quadratic.len());
+                self.quadratic.push((a.clone(), b.clone(), coefficient));
+            }
+        }
+    }
+
+    fn add_scaled(&mut self, other: &QuadraticTerms, factor: f64) {
+        for (a, b, coefficient) in &other.quadratic {
+            self.add_quadratic(a, b, factor * coefficient);
+        }
+        for (var, coefficient) in &other.linear {
+            self.add_linear(var, factor * coefficient);
+        }
+        self.constant += factor * other.constant;
+    }
+
+    /// Product of two decompositions, or `None` if its degree is higher than two
+    fn product(&self, other: &QuadraticTerms) -> Option<QuadraticTerms> {
+        if self.degree() + other.degree() > 2 {
+            return None;
+        }
+        let mut result = QuadraticTerms::default();
+        if other.constant != 0.0 {
+            result.add_scaled(self, other.constant);
+        }
+        if self.constant != 0.0 {
+            let mut other_terms = other.clone();
+            other_terms.constant = 0.0;
+            result.add_scaled(&other_terms, self.constant);
+        }
+        for (a, a_coefficient) in &self.linear {
+            for (b, b_coefficient) in &other.linear {
+                result.add_quadratic(a, b, a_coefficient * b_coefficient);
+            }
+        }
+        Some(result)
+    }
+
+    /// Remove the terms whose coefficients cancelled out
+    fn drop_zeros(&mut self) {
+        self.quadratic.retain(|&(_, _, coefficient)| coefficient != 0.0);
+        self.linear.retain(|&(_, coefficient)| coefficient != 0.0);
+        self.linear_positions.clear();
+        for (position, (var, _)) in self.linear.iter().enumerate() {
+            self.linear_positions.entry(var.var_name().unwrap().to_string()).or_default().push(position);
+        }
+        self.quadratic_positions = self.quadratic.iter().enumerate()
+            .map(|(position, (a, b, _))| ((a.var_name().unwrap().to_string(), b.var_name().unwrap().to_string()), position))
+            .collect();
+    }
+}
+
 impl LpExpression {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
        LpExpression {
            root: 0,
            arena: Vec::new()
        }
     }
 
-    pub fn literal(value: f32) -> Self {
+    pub fn literal(value: f64) -> Self {
         value.into()
     }
 
@@ -373,7 +597,152 @@ impl LpExpression {
         self.expr_ref_at(self.root)
     }
 
-    pub(crate) fn split_off_constant(&mut self) -> f32 {
+    /// Nodes reachable from the root, i.e. the nodes actually part of the expression
+    pub(crate) fn reachable_nodes(&self) -> Vec<&LpExprNode> {
+        let mut nodes = Vec::new();
+        if self.arena.is_empty() {
+            return nodes;
+        }
+        let mut remaining = vec![self.get_root_index()];
+        while let Some(index) = remaining.pop() {
+            let node = self.expr_ref_at(index);
+            if let LpCompExpr(_, left, right) = node {
+                remaining.push(*right);
+                remaining.push(*left);
+            }
+            nodes.push(node);
+        }
+        nodes
+    }
+
+    /// Overwrite every node for which `replacement` returns a new node
+    pub(crate) fn replace_nodes<F>(&mut self, replacement: F) where F: Fn(&LpExprNode) -> Option<LpExprNode> {
+        for index in 0..self.arena.len() {
+            if let Some(new_expr) = replacement(&self.arena[index]) {
+                self.overwrite_expr_at(index, new_expr);
+            }
+        }
+    }
+
+    /// Decompose a linear expression into its variables with their coefficients, and its constant
+    pub(crate) fn linear_terms(&self) -> Result<(Vec<(LpExprNode, f64)>, f64), String> {
+        let terms = self.quadratic_terms()?;
+        if let Some((a, b, _)) = terms.quadratic.first() {
+            return Err(format!("Non linear term: {} * {}", a.var_name().unwrap(), b.var_name().unwrap()));
+        }
+        Ok((terms.linear, terms.constant))
+    }
+
+    /// Decompose an expression of degree at most two into its products of variables,
+    /// its variables and its constant
+    pub(crate) fn quadratic_terms(&self) -> Result<QuadraticTerms, String> {
+        if self.arena.is_empty() {
+            return Ok(QuadraticTerms::default());
+        }
+        let mut terms = match self.quadratic_terms_at(self.get_root_index()) {
+            Ok(terms) => terms,
+            // products of higher degree may cancel out once simplified
+            Err(_) => {
+                let mut expr = self.clone();
+                expr.simplify();
+                expr.quadratic_terms_at(expr.get_root_index())?
+            }
+        };
+        terms.drop_zeros();
+        Ok(terms)
+    }
+
+    /// Canonical tree of the sum of `terms` and `constant`, built without simplification:
+    /// terms with a zero coefficient are dropped and the constant comes last. The tree is
+    /// empty if nothing is left.
+    pub(crate) fn from_linear_terms(terms: &[(LpExprNode, f64)], constant: f64) -> LpExpression {
+        let mut expr = LpExpression::new();
+        let mut root: Option<LpExprArenaIndex> = None;
+        for (var, coefficient) in terms.iter().filter(|&&(_, coefficient)| coefficient != 0.0) {
+            // the sign of the terms after the first one is carried by the operation
+            let shown = if root.is_some() { coefficient.abs() } else { *coefficient };
+            let var_index = expr.push_as_expr(var);
+            let term = if shown == 1.0 {
+                var_index
+            } else {
+                let lit_index = expr.push_as_expr(&LitVal(shown));
+                expr.push_as_expr(&LpCompExpr(Multiplication, lit_index, var_index))
+            };
+            root = Some(match root {
+                None => term,
+                Some(left) if *coefficient < 0.0 => expr.push_as_expr(&LpCompExpr(Subtraction, left, term)),
+                Some(left) => expr.push_as_expr(&LpCompExpr(Addition, left, term)),
+            });
+        }
+        if constant != 0.0 {
+            let lit_index = expr.push_as_expr(&LitVal(constant));
+            root = Some(match root {
+                None => lit_index,
+                Some(left) => expr.push_as_expr(&LpCompExpr(Addition, left, lit_index)),
+            });
+        }
+        if let Some(root) = root {
+            expr.set_root_to_index(root);
+        }
+        expr
+    }
+
+    fn quadratic_terms_at(&self, root: LpExprArenaIndex) -> Result<QuadraticTerms, String> {
+        let mut terms = QuadraticTerms::default();
+        let mut remaining = vec![(1.0, root)];
+        while let Some((factor, index)) = remaining.pop() {
+            match self.expr_ref_at(index) {
+                LitVal(c) => terms.constant += factor * c,
+                LpCompExpr(Multiplication, left, right) => {
+                    match (self.expr_ref_at(*left), self.expr_ref_at(*right)) {
+                        (LitVal(c), _) => remaining.push((factor * c, *right)),
+                        (_, LitVal(c)) => remaining.push((factor * c, *left)),
+                        _ => {
+                            let left_terms = self.quadratic_terms_at(*left)?;
+                            let right_terms = self.quadratic_terms_at(*right)?;
+                            match left_terms.product(&right_terms) {
+                                Some(product) => terms.add_scaled(&product, factor),
+                                None => return Err(format!("Expression of degree higher than two: {}", self.show(&index, true))),
+                            }
+                        }
+                    }
+                }
+                LpCompExpr(Addition, left, right) => {
+                    remaining.push((factor, *right));
+                    remaining.push((factor, *left));
+                }
+                LpCompExpr(Subtraction, left, right) => {
+                    remaining.push((-factor, *right));
+                    remaining.push((factor, *left));
+                }
+                EmptyExpr => {}
+                var => terms.add_linear(var, factor),
+            }
+        }
+        Ok(terms)
+    }
+
+    /// Whether the expression has no product of variables
+    pub fn is_linear(&self) -> bool {
+        self.quadratic_terms().map(|terms| terms.quadratic.is_empty()).unwrap_or(false)
+    }
+
+    /// Range of a linear expression over the bounds of its variables (`None` on a side where
+    /// it is unbounded)
+    pub fn bounds(&self) -> Result<(Option<f64>, Option<f64>), String> {
+        let (terms, constant) = self.linear_terms()?;
+        let mut lower = Some(constant);
+        let mut upper = Some(constant);
+        for (var, coefficient) in &terms {
+            let (low, up) = var.var_bounds().unwrap_or((None, None));
+            let (low, up) = if *coefficient >= 0.0 { (low, up) } else { (up, low) };
+            lower = lower.and_then(|l| low.map(|b| l + coefficient * b));
+            upper = upper.and_then(|u| up.map(|b| u + coefficient * b));
+        }
+        Ok((lower, upper))
+    }
+
+    pub(crate) fn split_off_constant(&mut self) -> f64 {
         match self.expr_clone_at(self.root) {
             LitVal(c) => {
                 self.clone_from(&LpExpression::new());
@@ -400,13 +769,18 @@ impl LpExpression {
     }
 
     pub(crate) fn merge_cloned_arenas(&self, right_lp_expr_arena: &LpExpression, operation: LpExprOp) -> Self {
-        let mut new_arena = self.clone();
-        let index_at_insertion = new_arena.push_arena_at_root(right_lp_expr_arena);
-        let new_root = new_arena.push_as_expr(
-            &LpCompExpr(operation, new_arena.get_root_index(), index_at_insertion)
+        self.clone().merge_arenas(right_lp_expr_arena, operation)
+    }
+
+    /// `self operation right`, appending the nodes of `right` to the arena of `self` instead of
+    /// copying it, so that a sum of n terms built with `+` or `+=` takes O(n)
+    pub(crate) fn merge_arenas(mut self, right_lp_expr_arena: &LpExpression, operation: LpExprOp) -> Self {
+        let index_at_insertion = self.push_arena_at_root(right_lp_expr_arena);
+        let new_root = self.push_as_expr(
+            &LpCompExpr(operation, self.get_root_index(), index_at_insertion)
         );
-        new_arena.set_root_to_index(new_root);
-        new_arena
+        self.set_root_to_index(new_root);
+        self
     }
 
     fn push_arena_at_root(&mut self, right_lp_expr_arena: &LpExpression) -> LpExprArenaIndex {
@@ -549,6 +923,8 @@ impl LpExpression {
                         ConsBin(LpBinary { name: ref n, .. }) => result += n,
                         ConsInt(LpInteger { name: ref n, .. }) => result += n,
                         ConsCont(LpContinuous { name: ref n, .. }) => result += n,
+                        ConsSemiCont(LpSemiContinuous { name: ref n, .. }) => result += n,
+                        ConsSemiInt(LpSemiInteger { name: ref n, .. }) => result += n,
                         EmptyExpr => { result += "EmptyExpr!!!" }
                     }
                 }
@@ -1253,6 +1629,8 @@ impl LpExpression {
                     ConsBin(_)
                     | ConsInt(_)
                     | ConsCont(_)
+                    | ConsSemiCont(_)
+                    | ConsSemiInt(_)
                     | LitVal(_)
                     | LpExprNode::EmptyExpr => { }
                 };
@@ -1272,6 +1650,8 @@ pub enum Constraint {
     GreaterOrEqual,
     LessOrEqual,
     Equal,
+    /// Range `rhs - width <= lhs <= rhs`, kept as a single row
+    Range(f64),
 }
 
 impl ToTokens for Constraint {
@@ -1281,40 +1661,57 @@ impl ToTokens for Constraint {
             Constraint::GreaterOrEqual => quote!(Constraint::GreaterOrEqual),
             Constraint::LessOrEqual => quote!(Constraint::LessOrEqual),
             Constraint::Equal => quote!(Constraint::Equal),
+            Constraint::Range(width) => quote!(Constraint::Range(#width)),
         });
     }
 }
 
+/// A constraint `lhs op rhs`, optionally named.
+///
+/// The name is used as the row identifier in the LP file and to look the constraint up
+/// on the problem or on a solution. Unnamed constraints are identified by their
+/// position (`c1`, `c2`, ...).
 #[derive(Debug, Clone, PartialEq)]
-pub struct LpConstraint(pub LpExpression, pub Constraint, pub LpExpression);
+pub struct LpConstraint(pub LpExpression, pub Constraint, pub LpExpression, pub Option<String>);
 
 impl LpConstraint {
+    pub fn new(lhs: LpExpression, op: Constraint, rhs: LpExpression) -> LpConstraint {
+        LpConstraint(lhs, op, rhs, None)
+    }
+
+    /// Give a name to the constraint
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use lp_modeler::dsl::*;
+    ///
+    /// let ref a = LpInteger::new("a");
+    /// let ref b = LpInteger::new("b");
+    ///
+    /// let mut problem = LpProblem::new("One Problem", LpObjective::Maximize);
+    /// problem += (a + b).le(10).with_name("capacity");
+    /// assert!(problem.constraint("capacity").is_some());
+    /// ```
+    pub fn with_name(&self, name: &str) -> LpConstraint {
+        LpConstraint(self.0.clone(), self.1.clone(), self.2.clone(), Some(name.to_string()))
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.3.as_deref()
+    }
+
     pub(crate) fn generalize(&self) -> LpConstraint {
         // TODO: Optimize tailrec
-        let &LpConstraint(ref lhs, ref op, ref rhs) = self;
+        let LpConstraint(ref lhs, ref op, ref rhs, ref name) = *self;
         let mut new_lhs_expr = lhs.merge_cloned_arenas(rhs, Subtraction);
-        let constant = new_lhs_expr.simplify().split_off_constant();
-        let new_rhs_expr_arena: LpExpression = LitVal(-constant).into();
-        LpConstraint(new_lhs_expr, (*op).clone(), new_rhs_expr_arena)
-    }
-
-    pub(crate) fn var(&self, expr_index: LpExprArenaIndex, constraint_index: usize, lst: &mut HashMap<String, (usize, LpExprArenaIndex)>) {
-        match self.0.expr_ref_at(expr_index) {
-            ConsBin(LpBinary { ref name, .. })
-            | ConsInt(LpInteger { ref name, .. })
-            | ConsCont(LpContinuous { ref name, .. }) => {
-                lst.insert(name.clone(), (constraint_index, expr_index) );
-            },
-            LpCompExpr(Multiplication, _, e) => {
-                self.var(*e, constraint_index, lst);
-            },
-            LpCompExpr(Addition, e1, e2)
-            | LpCompExpr(Subtraction, e1, e2) => {
-                self.var(*e1, constraint_index, lst);
-                self.var(*e2, constraint_index, lst);
-            }
-            _ => (),
+        if let Ok((terms, constant)) = new_lhs_expr.linear_terms() {
+            let new_rhs_expr_arena: LpExpression = LitVal(0.0 - constant).into();
+            return LpConstraint(LpExpression::from_linear_terms(&terms, 0.0), (*op).clone(), new_rhs_expr_arena, name.clone());
         }
+        let constant = new_lhs_expr.simplify().split_off_constant();
+        let new_rhs_expr_arena: LpExpression = LitVal(0.0 - constant).into();
+        LpConstraint(new_lhs_expr, (*op).clone(), new_rhs_expr_arena, name.clone())
     }
 }
 
@@ -1323,9 +1720,13 @@ impl ToTokens for LpConstraint {
         let lhs = &self.0;
         let constraint = &self.1;
         let rhs = &self.2;
+        let name = match self.3 {
+            Some(ref n) => quote!(Some(#n.to_string())),
+            None => quote!(None)
+        };
         stream.extend(quote!(
             LpConstraint(
-                #lhs, #constraint, #rhs
+                #lhs, #constraint, #rhs, #name
             )
         ));
     }
@@ -1416,14 +1817,14 @@ mod tests {
     fn simplifications() {
         let a = &LpInteger::new("a");
 
-        let expr1 = a - 2f32;
-        let expr2 = 1f32 - a;
+        let expr1 = a - 2f64;
+        let expr2 = 1f64 - a;
 
         let c = (expr1.clone() + expr2.clone()).simplify().split_off_constant();
-        assert_eq!(c, -1f32);
+        assert_eq!(c, -1f64);
 
         let c = (expr2.clone() + expr1.clone()).simplify().split_off_constant();
-        assert_eq!(c, -1f32);
+        assert_eq!(c, -1f64);
     }
 
     #[test]
@@ -1435,7 +1836,7 @@ mod tests {
             )
             .collect();
         let mut sum = lp_sum(&vars);
-        assert_eq!(sum.simplify().split_off_constant(), count as f32);
+        assert_eq!(sum.simplify().split_off_constant(), count as f64);
     }
 
     #[test]
@@ -1450,18 +1851,18 @@ mod tests {
         let quoted_exp_str = "LpExprNode :: ConsInt (".to_owned() + quoted_a_str + ")";
         assert_eq!(quoted_exp.to_string(), quoted_exp_str);
 
-        let full_exp_arena = LpExpression::build (0, vec![LpExprNode:: LpCompExpr (LpExprOp :: Multiplication, 1, 2), LpExprNode:: LpCompExpr (LpExprOp :: Subtraction, 3, 4 ), LpExprNode:: LpCompExpr (LpExprOp :: Addition, 5, 6), LpExprNode:: LitVal (1f32), LpExprNode:: EmptyExpr, LpExprNode:: ConsCont (LpContinuous { name : "x".to_string() , lower_bound : None , upper_bound : None }), LpExprNode:: ConsInt (LpInteger { name : "y".to_string() , lower_bound : None , upper_bound : None }) ] );
+        let full_exp_arena = LpExpression::build (0, vec![LpExprNode:: LpCompExpr (LpExprOp :: Multiplication, 1, 2), LpExprNode:: LpCompExpr (LpExprOp :: Subtraction, 3, 4 ), LpExprNode:: LpCompExpr (LpExprOp :: Addition, 5, 6), LpExprNode:: LitVal (1f64), LpExprNode:: EmptyExpr, LpExprNode:: ConsCont (LpContinuous { name : "x".to_string() , lower_bound : None , upper_bound : None }), LpExprNode:: ConsInt (LpInteger { name : "y".to_string() , lower_bound : None , upper_bound : None }) ] );
 
 
         let full_exp_quoted = quote!(#full_exp_arena);
-        let full_exp_str = "LpExpression { root : 0usize , arena : struct LpExprNode :: LpCompExpr (LpExprOp :: Multiplication , 1usize , 2usize) ; , struct LpExprNode :: LpCompExpr (LpExprOp :: Subtraction , 3usize , 4usize) ; , struct LpExprNode :: LpCompExpr (LpExprOp :: Addition , 5usize , 6usize) ; , struct LpExprNode :: LitVal (1f32) ; , struct LpExprNode :: EmptyExpr ; , struct LpExprNode :: ConsCont (LpContinuous { name : \"x\" . to_string () , lower_bound : None , upper_bound : None }) ; , struct LpExprNode :: ConsInt (LpInteger { name : \"y\" . to_string () , lower_bound : None , upper_bound : None }) ; }";
+        let full_exp_str = "LpExpression { root : 0usize , arena : struct LpExprNode :: LpCompExpr (LpExprOp :: Multiplication , 1usize , 2usize) ; , struct LpExprNode :: LpCompExpr (LpExprOp :: Subtraction , 3usize , 4usize) ; , struct LpExprNode :: LpCompExpr (LpExprOp :: Addition , 5usize , 6usize) ; , struct LpExprNode :: LitVal (1f64) ; , struct LpExprNode :: EmptyExpr ; , struct LpExprNode :: ConsCont (LpContinuous { name : \"x\" . to_string () , lower_bound : None , upper_bound : None }) ; , struct LpExprNode :: ConsInt (LpInteger { name : \"y\" . to_string () , lower_bound : None , upper_bound : None }) ; }";
         assert_eq!(full_exp_quoted.to_string(), full_exp_str);
 
         // a.equal(&b);
-        let a_eq_b = LpConstraint(LpExpression::build(0, vec![LpExprNode:: LpCompExpr(LpExprOp :: Subtraction, 1, 2), LpExprNode::ConsInt (LpInteger { name : "a".to_string() , lower_bound : None , upper_bound : None }), LpExprNode::ConsInt (LpInteger { name : "b".to_string() , lower_bound : None , upper_bound : None }) ] ), Constraint::Equal, LitVal(0f32).into());
+        let a_eq_b = LpConstraint(LpExpression::build(0, vec![LpExprNode:: LpCompExpr(LpExprOp :: Subtraction, 1, 2), LpExprNode::ConsInt (LpInteger { name : "a".to_string() , lower_bound : None , upper_bound : None }), LpExprNode::ConsInt (LpInteger { name : "b".to_string() , lower_bound : None , upper_bound : None }) ] ), Constraint::Equal, LitVal(0f64).into(), None);
 
         let quoted_a_eq_b = quote!(#a_eq_b);
-        let a_eq_b_str = "LpConstraint (LpExpression { root : 0usize , arena : struct LpExprNode :: LpCompExpr (LpExprOp :: Subtraction , 1usize , 2usize) ; , struct LpExprNode :: ConsInt (LpInteger { name : \"a\" . to_string () , lower_bound : None , upper_bound : None }) ; , struct LpExprNode :: ConsInt (LpInteger { name : \"b\" . to_string () , lower_bound : None , upper_bound : None }) ; } , Constraint :: Equal , LpExpression { root : 0usize , arena : struct LpExprNode :: LitVal (0f32) ; })";
+        let a_eq_b_str = "LpConstraint (LpExpression { root : 0usize , arena : struct LpExprNode :: LpCompExpr (LpExprOp :: Subtraction , 1usize , 2usize) ; , struct LpExprNode :: ConsInt (LpInteger { name : \"a\" . to_string () , lower_bound : None , upper_bound : None }) ; , struct LpExprNode :: ConsInt (LpInteger { name : \"b\" . to_string () , lower_bound : None , upper_bound : None }) ; } , Constraint :: Equal , LpExpression { root : 0usize , arena : struct LpExprNode :: LitVal (0f64) ; } , None)";
         assert_eq!(quoted_a_eq_b.to_string(), a_eq_b_str);
     }
 }
\ No newline at end of file
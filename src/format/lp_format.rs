@@ -1,6 +1,6 @@
 use std::fs::File;
 use std::io::prelude::*;
-use std::io::Result;
+use std::io::{Error, ErrorKind, Result};
 
 use dsl::*;
 use dsl::Constraint::*;
@@ -16,6 +16,17 @@ pub trait LpFileFormat {
 
 impl LpFileFormat for LpProblem {
 
+    /// Write the problem to an LP file, or fail with `ErrorKind::InvalidInput` if an
+    /// expression has a degree higher than two
+    fn write_lp(&self, file_model: &str) -> Result<()> {
+        if let Some(reason) = unsupported_expression(self) {
+            return Err(Error::new(ErrorKind::InvalidInput, reason));
+        }
+        let mut buffer = File::create(file_model)?;
+        buffer.write_all(self.to_lp_file_format().as_bytes())?;
+        Ok(())
+    }
+
     fn to_lp_file_format(&self) -> String {
 
         let mut buffer = String::new();
@@ -29,95 +40,133 @@ impl LpFileFormat for LpProblem {
             buffer.push_str(format!("\n\nSubject To\n{}", &constraints_block).as_str());
         }
 
-        let bounds_block = bounds_lp_file_block(self);
+        let variables = self.variables();
+
+        let bounds_block = bounds_lp_file_block(&variables);
         if bounds_block.len() > 0 {
             buffer.push_str(format!("\nBounds\n{}", &bounds_block).as_str());
         }
 
-        let integers_block = integers_lp_file_block(self);
+        let integers_block = integers_lp_file_block(&variables);
         if integers_block.len() > 0 {
             buffer.push_str(format!("\nGenerals\n  {}\n", &integers_block).as_str());
         }
 
-        let binaries_block = binaries_lp_file_block(self);
+        let binaries_block = binaries_lp_file_block(&variables);
         if binaries_block.len() > 0 {
             buffer.push_str(format!("\nBinary\n  {}\n", &binaries_block).as_str());
         }
 
+        let semi_continuous_block = semi_continuous_lp_file_block(&variables);
+        if !semi_continuous_block.is_empty() {
+            buffer.push_str(format!("\nSemi-continuous\n  {}\n", &semi_continuous_block).as_str());
+        }
+
+        let sos_block = sos_lp_file_block(self);
+        if !sos_block.is_empty() {
+            buffer.push_str(format!("\nSOS\n{}", &sos_block).as_str());
+        }
+
         buffer.push_str("\nEnd\n");
 
         buffer
     }
 }
 
+/// Reason why an expression of the problem has no LP syntax, if any
+fn unsupported_expression(prob: &LpProblem) -> Option<String> {
+    let objectives = prob.obj_expr_arena.iter()
+        .chain(prob.objectives.iter().map(|objective| &objective.expr));
+    let constraints = prob.constraints.iter()
+        .chain(prob.indicators.iter().map(|indicator| &indicator.constraint))
+        .flat_map(|constraint| vec![&constraint.0, &constraint.2]);
+    objectives.chain(constraints).find_map(|expr| expr.quadratic_terms().err())
+}
+
 fn objective_lp_file_block(prob: &LpProblem) -> String {
     // Write objectives
     let obj_type = match prob.objective_type {
         LpObjective::Maximize => "Maximize\n  ",
         LpObjective::Minimize => "Minimize\n  "
     };
+    if prob.has_multi_objectives() {
+        return multi_objectives_lp_file_block(prob, obj_type.trim_end());
+    }
     match &prob.obj_expr_arena {
         Some(expr_arena) => {
-            format!("{}obj: {}", obj_type, expr_arena.to_lp_file_format())
+            // quadratic objectives are written as [ ... ] / 2 with doubled coefficients
+            match expr_arena.quadratic_terms() {
+                Ok(ref terms) if !terms.quadratic.is_empty() => {
+                    format!("{}obj: {}", obj_type, quadratic_lp_file_format(terms, 2.0, " / 2"))
+                }
+                Ok(ref terms) => format!("{}obj: {}", obj_type, linear_lp_file_format(&terms.linear, terms.constant)),
+                // a degree higher than two has no LP syntax: rejected by `write_lp`
+                Err(reason) => format!("{}obj: \\ {}", obj_type, reason),
+            }
         }
         _ => String::new()
     }
 }
+/// Multi-objective section of Gurobi
+fn multi_objectives_lp_file_block(prob: &LpProblem, obj_type: &str) -> String {
+    let mut res = format!("{} multi-objectives", obj_type);
+    for objective in prob.multi_objectives() {
+        res.push_str(&format!(
+            "\n  {}: Priority={} Weight={} AbsTol={} RelTol={}\n    {}",
+            objective.name,
+            objective.priority,
+            objective.weight,
+            objective.abs_tol,
+            objective.rel_tol,
+            objective.expr.to_lp_file_format()
+        ));
+    }
+    res
+}
+
 fn constraints_lp_file_block(prob: &LpProblem) -> String {
     let mut res = String::new();
-    let mut constraints = prob.constraints.iter();
-    let mut index = 1;
-    while let Some(constraint) = constraints.next() {
-        res.push_str(&format!("  c{}: {}\n", index.to_string(), constraint.to_lp_file_format()));
-        index += 1;
+    for (index, constraint) in prob.constraints.iter().enumerate() {
+        let id = prob.constraint_id(index).unwrap();
+        res.push_str(&format!("  {}: {}\n", id, constraint.to_lp_file_format()));
+    }
+    for (index, indicator) in prob.indicators.iter().enumerate() {
+        let id = prob.indicator_id(index).unwrap();
+        res.push_str(&format!("  {}: {}\n", id, indicator.to_lp_file_format()));
     }
     res
 }
 
-fn bounds_lp_file_block(prob: &LpProblem) -> String {
+fn bounds_lp_file_block(variables: &LpVariables) -> String {
     let mut res = String::new();
-    for (_, (constraint_index, lp_expr_arena_index)) in prob.variables() {
-        let expr_ref = prob.constraints.get(constraint_index).unwrap().0.expr_ref_at(lp_expr_arena_index);
-        match expr_ref {
-            &LpExprNode::ConsInt(LpInteger {
-                         ref name,
-                         lower_bound,
-                         upper_bound,
-                     })
-            | &LpExprNode::ConsCont(LpContinuous {
-                            ref name,
-                            lower_bound,
-                            upper_bound,
-                        }) => {
-                if let Some(l) = lower_bound {
-                    res.push_str(&format!("  {} <= {}", &l.to_string(), &name));
-                    if let Some(u) = upper_bound {
-                        res.push_str(&format!(" <= {}", &u.to_string()));
-                    }
-                    res.push_str("\n");
-                } else if let Some(u) = upper_bound {
-                    res.push_str(&format!("  {} <= {}\n", &name, &u.to_string()));
-                } else {
-                    match expr_ref {
-                        &LpExprNode::ConsCont(LpContinuous { .. }) => {
-                            res.push_str(&format!("  {} free\n", &name));
-                        } // TODO: IntegerVar => -INF to INF
-                        _ => (),
-                    }
+    for var in variables {
+        let name = var.name();
+        match var.bounds() {
+            (Some(l), upper_bound) => {
+                res.push_str(&format!("  {} <= {}", &l.to_string(), &name));
+                if let Some(u) = upper_bound {
+                    res.push_str(&format!(" <= {}", &u.to_string()));
                 }
+                res.push_str("\n");
             }
-            _ => (),
+            (None, Some(u)) => res.push_str(&format!("  {} <= {}\n", &name, &u.to_string())),
+            (None, None) => match var {
+                &LpVariable::Continuous(_) => {
+                    res.push_str(&format!("  {} free\n", &name));
+                } // TODO: IntegerVar => -INF to INF
+                _ => (),
+            },
         }
     }
     res
 }
 
-fn integers_lp_file_block(prob: &LpProblem) -> String {
+fn integers_lp_file_block(variables: &LpVariables) -> String {
     let mut res = String::new();
-    for (_, (constraint_index, lp_expr_arena_index)) in prob.variables() {
-        match prob.constraints.get(constraint_index).unwrap().0.expr_ref_at(lp_expr_arena_index) {
-            &LpExprNode::ConsInt(LpInteger { ref name, .. }) => {
-                res.push_str(format!("{} ", name).as_str());
+    for var in variables {
+        match var {
+            &LpVariable::Integer(_) | &LpVariable::SemiInteger(_) => {
+                res.push_str(format!("{} ", var.name()).as_str());
             }
             _ => (),
         }
@@ -125,21 +174,102 @@ fn integers_lp_file_block(prob: &LpProblem) -> String {
     res
 }
 
-fn binaries_lp_file_block(prob: &LpProblem) -> String  {
+fn semi_continuous_lp_file_block(variables: &LpVariables) -> String {
     let mut res = String::new();
-    for (_, (constraint_index, lp_expr_arena_index)) in prob.variables() {
-        match prob.constraints.get(constraint_index).unwrap().0.expr_ref_at(lp_expr_arena_index) {
-            &LpExprNode::ConsBin(LpBinary { ref name }) => {
-                res.push_str(format!("{} ", name).as_str());
-            }
-            _ => (),
+    for var in variables {
+        if var.is_semi_continuous() {
+            res.push_str(format!("{} ", var.name()).as_str());
+        }
+    }
+    res
+}
+
+fn binaries_lp_file_block(variables: &LpVariables) -> String  {
+    let mut res = String::new();
+    for var in variables {
+        if let &LpVariable::Binary(LpBinary { ref name }) = var {
+            res.push_str(format!("{} ", name).as_str());
+        }
+    }
+    res
+}
+
+fn sos_lp_file_block(prob: &LpProblem) -> String {
+    let mut res = String::new();
+    for sos in &prob.sos {
+        let sos_type = match sos.sos_type {
+            SosType::Sos1 => "S1",
+            SosType::Sos2 => "S2",
+        };
+        let vars: Vec<String> = sos.weighted_vars.iter()
+            .map(|(name, weight)| format!("{}:{}", name, weight))
+            .collect();
+        res.push_str(&format!("  {}: {}:: {}\n", sos.name, sos_type, vars.join(" ")));
+    }
+    res
+}
+
+fn push_lp_term(res: &mut String, coefficient: f64, term: &str) {
+    if res.is_empty() {
+        if coefficient < 0.0 {
+            res.push('-');
         }
+    } else if coefficient < 0.0 {
+        res.push_str(" - ");
+    } else {
+        res.push_str(" + ");
+    }
+    if coefficient.abs() != 1.0 {
+        res.push_str(&format!("{} ", coefficient.abs()));
+    }
+    res.push_str(term);
+}
+
+fn quadratic_lp_file_format(terms: &QuadraticTerms, quadratic_factor: f64, suffix: &str) -> String {
+    let mut res = String::new();
+    for (var, coefficient) in &terms.linear {
+        push_lp_term(&mut res, *coefficient, var.var_name().unwrap());
+    }
+    let mut products = String::new();
+    for (a, b, coefficient) in &terms.quadratic {
+        let (a, b) = (a.var_name().unwrap(), b.var_name().unwrap());
+        let product = if a == b { format!("{} ^ 2", a) } else { format!("{} * {}", a, b) };
+        push_lp_term(&mut products, quadratic_factor * coefficient, &product);
+    }
+    if !res.is_empty() {
+        res.push_str(" + ");
+    }
+    res.push_str(&format!("[ {} ]{}", products, suffix));
+    if terms.constant != 0.0 {
+        let sign = if terms.constant < 0.0 { '-' } else { '+' };
+        res.push_str(&format!(" {} {}", sign, terms.constant.abs()));
+    }
+    res
+}
+
+fn linear_lp_file_format(terms: &[(LpExprNode, f64)], constant: f64) -> String {
+    let mut res = String::new();
+    for (var, coefficient) in terms.iter().filter(|&&(_, coefficient)| coefficient != 0.0) {
+        push_lp_term(&mut res, *coefficient, var.var_name().unwrap());
+    }
+    if res.is_empty() {
+        res.push_str(&constant.to_string());
+    } else if constant != 0.0 {
+        let sign = if constant < 0.0 { '-' } else { '+' };
+        res.push_str(&format!(" {} {}", sign, constant.abs()));
     }
     res
 }
 
 impl LpFileFormat for LpExpression {
     fn to_lp_file_format(&self) -> String {
+        // written from the canonical terms, unless the degree is higher than two
+        if let Ok(ref terms) = self.quadratic_terms() {
+            if terms.quadratic.is_empty() {
+                return linear_lp_file_format(&terms.linear, terms.constant);
+            }
+            return quadratic_lp_file_format(terms, 1.0, "");
+        }
         fn formalize_signs(s: String) -> String {
             let mut s = s.clone();
             let mut t = "".to_string();
@@ -159,17 +289,37 @@ impl LpFileFormat for LpExpression {
     }
 }
 
+impl LpFileFormat for LpLinearExpression {
+    fn to_lp_file_format(&self) -> String {
+        linear_lp_file_format(self.var_terms(), self.constant())
+    }
+}
 
 impl LpFileFormat for LpConstraint {
     fn to_lp_file_format(&self) -> String {
         let mut res = String::new();
+        if let Range(width) = self.1 {
+            res.push_str(&(&self.2 - width).to_lp_file_format());
+            res.push_str(" <= ");
+        }
         res.push_str(&self.0.to_lp_file_format());
         match self.1 {
             GreaterOrEqual => res.push_str(" >= "),
-            LessOrEqual => res.push_str(" <= "),
+            LessOrEqual | Range(_) => res.push_str(" <= "),
             Equal => res.push_str(" = "),
         }
         res.push_str(&self.2.to_lp_file_format());
         res
     }
 }
+
+impl LpFileFormat for LpIndicator {
+    fn to_lp_file_format(&self) -> String {
+        format!(
+            "{} = {} -> {}",
+            self.binary.name,
+            if self.active { 1 } else { 0 },
+            self.constraint.to_lp_file_format()
+        )
+    }
+}
@@ -6,6 +6,14 @@ extern crate quote;
 extern crate coin_cbc;
 #[cfg(feature = "minilp")]
 extern crate minilp;
+#[cfg(feature = "macros")]
+extern crate lp_modeler_macros;
+#[cfg(feature = "serde")]
+extern crate serde;
+
+/// Declarative model definitions (see the `lp-modeler-macros` crate)
+#[cfg(feature = "macros")]
+pub use lp_modeler_macros::lp_model;
 
 pub mod util;
 
@@ -14,12 +22,29 @@ pub mod dsl {
     pub use self::variables::*;
     pub mod operations;
     pub use self::operations::*;
+    pub mod linear_expression;
+    pub use self::linear_expression::*;
     pub mod problem;
     pub use self::problem::*;
+    pub mod multi_objective;
+    pub use self::multi_objective::*;
+    pub mod indexed;
+    pub use self::indexed::*;
+    pub mod piecewise;
+    pub use self::piecewise::*;
+    pub mod linearization;
+    pub use self::linearization::*;
+    pub mod logic;
+    pub use self::logic::*;
+    pub mod registry;
+    pub use self::registry::*;
+    pub mod validation;
+    pub use self::validation::*;
 }
 
 pub mod format {
    pub mod lp_format;
+   pub(crate) mod json;
 }
 
 pub mod solvers;
@@ -4,12 +4,13 @@ use self::uuid::Uuid;
 use std::fs;
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufReader, BufRead};
+use std::io::{BufReader, BufRead, Write};
+use std::path::Path;
 use std::process::Command;
 
 use dsl::LpProblem;
 use format::lp_format::*;
-use solvers::{Status, SolverTrait, WithMaxSeconds, WithNbThreads, SolverWithSolutionParsing, Solution};
+use solvers::{Status, SolverTrait, WithMaxSeconds, WithNbThreads, WithValidation, WithInitialSolution, SolverWithSolutionParsing, SolverWithSolutionPool, Solution, SolutionPool, solve_lexicographic, initial_values};
 
 #[derive(Debug, Clone)]
 pub struct CbcSolver {
@@ -18,6 +19,8 @@ pub struct CbcSolver {
     temp_solution_file: String,
     threads: Option<u32>,
     seconds: Option<u32>,
+    validate: bool,
+    initial_solution: Option<HashMap<String, f64>>,
 }
 
 impl CbcSolver {
@@ -28,6 +31,8 @@ impl CbcSolver {
             temp_solution_file: format!("{}.sol", Uuid::new_v4().to_string()),
             threads: None,
             seconds: None,
+            validate: false,
+            initial_solution: None,
         }
     }
 
@@ -38,6 +43,8 @@ impl CbcSolver {
             temp_solution_file: self.temp_solution_file.clone(),
             threads: None,
             seconds: None,
+            validate: self.validate,
+            initial_solution: self.initial_solution.clone(),
         }
     }
 
@@ -48,8 +55,90 @@ impl CbcSolver {
             temp_solution_file,
             threads: None,
             seconds: None,
+            validate: self.validate,
+            initial_solution: self.initial_solution.clone(),
         }
     }
+
+    /// Write the initial solution for the variables of `problem` to `path`, in the solution
+    /// format read by the `mipstart` command of cbc
+    pub fn write_initial_solution(&self, path: &str, problem: &LpProblem) -> Result<(), String> {
+        let values = self.initial_solution.as_ref().ok_or("No initial solution")?;
+        let mut content = String::from("Stopped on iterations - objective value 0\n");
+        for (index, (name, value)) in initial_values(values, &problem.variables()).iter().enumerate() {
+            content.push_str(&format!("{} {} {} 0\n", index, name, value));
+        }
+        File::create(path)
+            .and_then(|mut file| file.write_all(content.as_bytes()))
+            .map_err(|e| e.to_string())
+    }
+
+    /// Solve `problem` and read the best solution, followed by the next best solutions saved
+    /// by cbc if `saved_files` are given
+    fn solve_saving<'a>(&self, problem: &'a LpProblem, saved_files: &[String]) -> Result<Vec<Solution<'a>>, String> {
+        // the LP reader of cbc has no quadratic section
+        if problem.has_quadratic_objective() || problem.has_quadratic_constraints() {
+            return Err(format!("Quadratic objectives and constraints are not supported by the {} solver", self.name));
+        }
+        let file_model = format!("{}.lp", problem.unique_name);
+        // Cbc does not read indicator constraints from LP files
+        if problem.indicators.is_empty() {
+            problem.write_lp(&file_model).map_err(|e| e.to_string())?;
+        } else {
+            problem.reformulate_indicators()?.write_lp(&file_model).map_err(|e| e.to_string())?;
+        }
+
+        let mut params: HashMap<String, String> = Default::default();
+        let optional_params: Vec<Option<(String, u32)>> = vec![
+            self.max_seconds().map(|s| ("seconds".to_owned(), s )),
+            self.nb_threads().map(|t| ("threads".to_owned(), t)) ];
+
+        for (arg, value) in optional_params.iter().flatten() {
+            params.insert(arg.to_string(), value.to_string());
+        }
+        // print the rows too, for their duals
+        params.insert("printingOptions".to_string(), "all".to_string());
+        let file_start = format!("{}.mst", problem.unique_name);
+        if self.initial_solution.is_some() {
+            self.write_initial_solution(&file_start, problem)?;
+            params.insert("mipstart".to_string(), file_start.clone());
+        }
+        if !saved_files.is_empty() {
+            params.insert("maxSavedSolutions".to_string(), (saved_files.len() + 1).to_string());
+        }
+        params.iter().for_each( |(a,b)| println!("{},{}",a,b));
+
+        // the next best saved solutions are written after the best one
+        let next_best = saved_files.iter().flat_map(|file| vec!["nextBestSolution", file.as_str()]);
+
+        let result = Command::new(&self.command_name)
+            .arg(&file_model)
+            .args(params.iter().flat_map(|(k, v)| vec![k, v]))
+            .arg("solve")
+            .arg("solution")
+            .arg(&self.temp_solution_file)
+            .args(next_best)
+            .output()
+            .map_err(|_| format!("Error running the {} solver", self.name))
+            .and_then(|r| {
+                if r.status.success() {
+                    let mut solutions = vec![self.read_solution(&self.temp_solution_file, Some(problem))?];
+                    for file in saved_files.iter().filter(|file| Path::new(file).exists()) {
+                        solutions.push(self.read_solution(file, Some(problem))?);
+                    }
+                    Ok(solutions)
+                } else {
+                    Err(r.status.to_string())
+                }
+            });
+
+        let _ = fs::remove_file(file_model);
+        let _ = fs::remove_file(file_start);
+        for file in saved_files {
+            let _ = fs::remove_file(file);
+        }
+        result
+    }
 }
 
 impl SolverWithSolutionParsing for CbcSolver {
@@ -59,9 +148,8 @@ impl SolverWithSolutionParsing for CbcSolver {
         // populate default values for all vars
         // CBC keeps only non-zero values from a number of variables
         if let Some(p) = problem {
-            let variables = p.variables();
-            for (name, _) in variables {
-                vars_value.insert(name.clone(), 0.0);
+            for name in p.variables().names() {
+                vars_value.insert(name.to_string(), 0.0);
             }
         }
 
@@ -82,6 +170,9 @@ impl SolverWithSolutionParsing for CbcSolver {
         } else {
             return Err("Incorrect solution format".to_string());
         };
+        // lines "index name value dual", for the rows (printed with `printingOptions all`)
+        // and then the columns, each one numbered from 0
+        let mut lines = Vec::new();
         for line in file.lines() {
             let l = line.unwrap();
             let mut result_line: Vec<_> = l.split_whitespace().collect();
@@ -89,21 +180,30 @@ impl SolverWithSolutionParsing for CbcSolver {
                 result_line.remove(0);
             };
             if result_line.len() == 4 {
-                match result_line[2].parse::<f32>() {
-                    Ok(n) => {
-                        vars_value.insert(result_line[1].to_string(), n);
-                    }
-                    Err(e) => return Err(e.to_string()),
-                }
+                let value = result_line[2].parse::<f64>().map_err(|e| e.to_string())?;
+                let dual = result_line[3].parse::<f64>().map_err(|e| e.to_string())?;
+                lines.push((result_line[0] == "0", result_line[1].to_string(), value, dual));
             } else {
                 return Err("Incorrect solution format".to_string());
             }
         }
-        if let Some(p) = problem {
-            Ok( Solution::with_problem(status, vars_value, p) )
-        } else {
-            Ok( Solution::new(status, vars_value) )
+        let nb_rows = lines.iter().skip(1).position(|&(first, _, _, _)| first).map_or(0, |position| position + 1);
+        let mut duals = HashMap::new();
+        let mut reduced_costs = HashMap::new();
+        for (index, (_, name, value, dual)) in lines.into_iter().enumerate() {
+            if index < nb_rows {
+                duals.insert(name, dual);
+            } else {
+                vars_value.insert(name.clone(), value);
+                reduced_costs.insert(name, dual);
+            }
         }
+        let solution = if let Some(p) = problem {
+            Solution::with_problem(status, vars_value, p)
+        } else {
+            Solution::new(status, vars_value)
+        };
+        Ok(solution.with_duals(duals, reduced_costs))
     }
 }
 
@@ -130,40 +230,58 @@ impl WithNbThreads<CbcSolver> for CbcSolver {
     }
 }
 
+impl WithValidation<CbcSolver> for CbcSolver {
+    fn validation(&self) -> bool {
+        self.validate
+    }
+    fn with_validation(&self, validate: bool) -> CbcSolver {
+        CbcSolver {
+            validate,
+            ..(*self).clone()
+        }
+    }
+}
+
+impl WithInitialSolution<CbcSolver> for CbcSolver {
+    fn initial_solution(&self) -> Option<&HashMap<String, f64>> {
+        self.initial_solution.as_ref()
+    }
+    fn with_initial_solution(&self, values: HashMap<String, f64>) -> CbcSolver {
+        CbcSolver {
+            initial_solution: Some(values),
+            ..(*self).clone()
+        }
+    }
+}
+
 impl SolverTrait for CbcSolver {
     type P = LpProblem;
 
     fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, String> {
-        let file_model = format!("{}.lp", problem.unique_name);
-        problem.write_lp(&file_model).map_err(|e| e.to_string())?;
-
-        let mut params: HashMap<String, String> = Default::default();
-        let optional_params: Vec<Option<(String, u32)>> = vec![
-            self.max_seconds().map(|s| ("seconds".to_owned(), s )),
-            self.nb_threads().map(|t| ("threads".to_owned(), t)) ];
-
-        for (arg, value) in optional_params.iter().flatten() {
-            params.insert(arg.to_string(), value.to_string());
+        if self.validate {
+            problem.validate().check()?;
         }
-        params.iter().for_each( |(a,b)| println!("{},{}",a,b));
-
-        let result = Command::new(&self.command_name)
-            .arg(&file_model)
-            .args(params.iter().flat_map(|(k, v)| vec![k, v]))
-            .arg("solve")
-            .arg("solution")
-            .arg(&self.temp_solution_file)
-            .output()
-            .map_err(|_| format!("Error running the {} solver", self.name))
-            .and_then(|r| {
-                if r.status.success() {
-                    self.read_solution(&self.temp_solution_file, Some(problem))
-                } else {
-                    Err(r.status.to_string())
-                }
-            });
+        if problem.has_multi_objectives() {
+            return solve_lexicographic(self, problem);
+        }
+        self.solve_saving(problem, &[]).map(|mut solutions| solutions.remove(0))
+    }
+}
 
-        let _ = fs::remove_file(file_model);
-        result
+impl SolverWithSolutionPool for CbcSolver {
+    /// Solutions saved by cbc during the search (`maxSavedSolutions`)
+    fn run_pool<'a>(&self, problem: &'a LpProblem, pool: &SolutionPool) -> Result<Vec<Solution<'a>>, String> {
+        if problem.has_multi_objectives() {
+            return pool.run(self, problem);
+        }
+        if self.validate {
+            problem.validate().check()?;
+        }
+        let saved_files: Vec<String> = (1..pool.max_solutions)
+            .map(|k| format!("{}.{}", self.temp_solution_file, k))
+            .collect();
+        let solutions = self.solve_saving(problem, &saved_files)?;
+        pool.select(problem, solutions)
     }
 }
+
@@ -0,0 +1,214 @@
+//! Verification of the values of a solution against the constraints of a problem.
+use std::collections::HashMap;
+use std::fmt;
+
+use dsl::{LpProblem, LpVariable, SosType};
+use solvers::{ConstraintActivity, Solution};
+
+/// Requirement of the problem not met by the values of a solution. The magnitude of each
+/// violation is the distance to the nearest value meeting the requirement.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LpViolation {
+    /// The activity of a constraint (see `ConstraintActivity`) is out of its bounds
+    Constraint { constraint: String, activity: f64, magnitude: f64 },
+    /// The constraint of an active indicator is not satisfied
+    Indicator { constraint: String, activity: f64, magnitude: f64 },
+    /// A variable is out of its bounds
+    Bound { name: String, value: f64, magnitude: f64 },
+    /// An integer or binary variable has a fractional value
+    Integrality { name: String, value: f64, magnitude: f64 },
+    /// A semi-continuous variable is neither 0 nor within its bounds
+    SemiContinuous { name: String, value: f64, magnitude: f64 },
+    /// Too many variables of a special ordered set are non-zero; the magnitude is the sum of
+    /// the absolute values exceeding those allowed
+    SpecialOrderedSet { set: String, magnitude: f64 },
+}
+
+impl LpViolation {
+    pub fn magnitude(&self) -> f64 {
+        use self::LpViolation::*;
+        match self {
+            Constraint { magnitude, .. }
+            | Indicator { magnitude, .. }
+            | Bound { magnitude, .. }
+            | Integrality { magnitude, .. }
+            | SemiContinuous { magnitude, .. }
+            | SpecialOrderedSet { magnitude, .. } => *magnitude,
+        }
+    }
+}
+
+impl fmt::Display for LpViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::LpViolation::*;
+        match self {
+            Constraint { constraint, activity, magnitude } => write!(f, "constraint {} is violated by {} (activity {})", constraint, magnitude, activity),
+            Indicator { constraint, activity, magnitude } => write!(f, "indicator {} is violated by {} (activity {})", constraint, magnitude, activity),
+            Bound { name, value, magnitude } => write!(f, "variable {} = {} is out of its bounds by {}", name, value, magnitude),
+            Integrality { name, value, magnitude } => write!(f, "variable {} = {} is fractional by {}", name, value, magnitude),
+            SemiContinuous { name, value, magnitude } => write!(f, "semi-continuous variable {} = {} is out of its bounds by {}", name, value, magnitude),
+            SpecialOrderedSet { set, magnitude } => write!(f, "set {} has too many non-zero variables (excess {})", set, magnitude),
+        }
+    }
+}
+
+/// Result of `FeasibilityCheck::check`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LpFeasibilityReport {
+    pub violations: Vec<LpViolation>,
+}
+
+impl LpFeasibilityReport {
+    pub fn is_feasible(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Largest magnitude of the violations (0 if feasible)
+    pub fn max_violation(&self) -> f64 {
+        self.violations.iter().map(|violation| violation.magnitude()).fold(0.0, f64::max)
+    }
+
+    /// `Err` listing the violations, if any
+    pub fn check(&self) -> Result<(), String> {
+        if self.is_feasible() {
+            Ok(())
+        } else {
+            let violations: Vec<String> = self.violations.iter().map(|v| v.to_string()).collect();
+            Err(format!("Infeasible solution: {}", violations.join("; ")))
+        }
+    }
+}
+
+/// Verify that values of the variables satisfy the constraints, indicators, special ordered
+/// sets, bounds and integrality requirements of a problem, up to absolute tolerances.
+/// A variable without value is taken as 0.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use lp_modeler::dsl::*;
+/// use lp_modeler::solvers::{FeasibilityCheck, LpViolation};
+///
+/// let ref a = LpInteger::new("a").lower_bound(0.0);
+/// let ref b = LpContinuous::new("b").upper_bound(5.0);
+///
+/// let mut problem = LpProblem::new("Plan", LpObjective::Maximize);
+/// problem += a + b;
+/// problem += (a + b).le(10).with_name("capacity");
+///
+/// let values: HashMap<String, f64> = vec![("a".to_string(), 5.5), ("b".to_string(), 5.0)].into_iter().collect();
+/// let report = FeasibilityCheck::new().check(&problem, &values).unwrap();
+/// assert_eq!(report.violations.len(), 2);
+/// assert_eq!(report.max_violation(), 0.5);
+/// assert!(report.check().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeasibilityCheck {
+    /// Violation allowed for constraints, indicators, sets and bounds
+    pub feasibility_tol: f64,
+    /// Distance allowed between the value of an integer variable and the nearest integer
+    pub integrality_tol: f64,
+}
+
+impl Default for FeasibilityCheck {
+    fn default() -> Self {
+        FeasibilityCheck::new()
+    }
+}
+
+impl FeasibilityCheck {
+    /// Check with tolerances of 1e-6 for feasibility and 1e-5 for integrality
+    pub fn new() -> FeasibilityCheck {
+        FeasibilityCheck {
+            feasibility_tol: 1e-6,
+            integrality_tol: 1e-5,
+        }
+    }
+
+    pub fn with_feasibility_tol(&self, feasibility_tol: f64) -> FeasibilityCheck {
+        FeasibilityCheck { feasibility_tol, ..self.clone() }
+    }
+
+    pub fn with_integrality_tol(&self, integrality_tol: f64) -> FeasibilityCheck {
+        FeasibilityCheck { integrality_tol, ..self.clone() }
+    }
+
+    /// Violations of `problem` by `values`, or `Err` if an expression has a degree higher
+    /// than two
+    pub fn check(&self, problem: &LpProblem, values: &HashMap<String, f64>) -> Result<LpFeasibilityReport, String> {
+        let mut report = LpFeasibilityReport::default();
+        for (index, constraint) in problem.constraints.iter().enumerate() {
+            let activity = ConstraintActivity::of(constraint, &problem.constraint_id(index).unwrap(), values)?;
+            if -activity.slack > self.feasibility_tol {
+                report.violations.push(LpViolation::Constraint {
+                    constraint: activity.id,
+                    activity: activity.activity,
+                    magnitude: -activity.slack,
+                });
+            }
+        }
+        for (index, indicator) in problem.indicators.iter().enumerate() {
+            let value = value_of(values, &indicator.binary.name);
+            if (value.round() == 1.0) != indicator.active {
+                continue;
+            }
+            let activity = ConstraintActivity::of(&indicator.constraint, &problem.indicator_id(index).unwrap(), values)?;
+            if -activity.slack > self.feasibility_tol {
+                report.violations.push(LpViolation::Indicator {
+                    constraint: activity.id,
+                    activity: activity.activity,
+                    magnitude: -activity.slack,
+                });
+            }
+        }
+        for sos in &problem.sos {
+            let mut weighted: Vec<(f64, f64)> = sos.weighted_vars.iter()
+                .map(|(name, weight)| (*weight, value_of(values, name).abs()))
+                .collect();
+            weighted.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+            let total: f64 = weighted.iter().map(|&(_, value)| value).sum();
+            let allowed = match sos.sos_type {
+                SosType::Sos1 => weighted.iter().map(|&(_, value)| value).fold(0.0, f64::max),
+                SosType::Sos2 if weighted.len() < 2 => total,
+                SosType::Sos2 => weighted.windows(2).map(|pair| pair[0].1 + pair[1].1).fold(0.0, f64::max),
+            };
+            if total - allowed > self.feasibility_tol {
+                report.violations.push(LpViolation::SpecialOrderedSet { set: sos.name.clone(), magnitude: total - allowed });
+            }
+        }
+        for var in &problem.variables() {
+            let name = var.name().to_string();
+            let value = value_of(values, &name);
+            let (lower, upper) = match var {
+                LpVariable::Binary(_) => (Some(0.0), Some(1.0)),
+                _ => var.bounds(),
+            };
+            let out_of_bounds = (lower.map_or(0.0, |lower| lower - value))
+                .max(upper.map_or(0.0, |upper| value - upper));
+            if var.is_semi_continuous() {
+                if out_of_bounds > self.feasibility_tol && value.abs() > self.feasibility_tol {
+                    let magnitude = out_of_bounds.min(value.abs());
+                    report.violations.push(LpViolation::SemiContinuous { name: name.clone(), value, magnitude });
+                }
+            } else if out_of_bounds > self.feasibility_tol {
+                report.violations.push(LpViolation::Bound { name: name.clone(), value, magnitude: out_of_bounds });
+            }
+            let fractional = (value - value.round()).abs();
+            if var.is_integer() && fractional > self.integrality_tol {
+                report.violations.push(LpViolation::Integrality { name, value, magnitude: fractional });
+            }
+        }
+        Ok(report)
+    }
+
+    /// Violations of the related problem of `solution` by its values
+    pub fn check_solution(&self, solution: &Solution) -> Result<LpFeasibilityReport, String> {
+        let problem = solution.related_problem.ok_or("The solution has no related problem")?;
+        self.check(problem, &solution.results)
+    }
+}
+
+fn value_of(values: &HashMap<String, f64>, name: &str) -> f64 {
+    *values.get(name).unwrap_or(&0.0)
+}
@@ -9,12 +9,14 @@ use std::process::Command;
 
 use dsl::LpProblem;
 use format::lp_format::*;
-use solvers::{Status, SolverTrait, SolverWithSolutionParsing, Solution};
+use solvers::{Status, SolverTrait, SolverWithSolutionParsing, SolverWithSolutionPool, Solution, WithValidation, solve_lexicographic};
 
+#[derive(Debug, Clone)]
 pub struct GlpkSolver {
     name: String,
     command_name: String,
     temp_solution_file: String,
+    validate: bool,
 }
 
 impl GlpkSolver {
@@ -23,6 +25,7 @@ impl GlpkSolver {
             name: "Glpk".to_string(),
             command_name: "glpsol".to_string(),
             temp_solution_file: format!("{}.sol", Uuid::new_v4().to_string()),
+            validate: false,
         }
     }
     pub fn command_name(&self, command_name: String) -> GlpkSolver {
@@ -30,6 +33,7 @@ impl GlpkSolver {
             name: self.name.clone(),
             command_name,
             temp_solution_file: self.temp_solution_file.clone(),
+            validate: self.validate,
         }
     }
     pub fn with_temp_solution_file(&self, temp_solution_file: String) -> GlpkSolver {
@@ -37,6 +41,7 @@ impl GlpkSolver {
             name: self.name.clone(),
             command_name: self.command_name.clone(),
             temp_solution_file,
+            validate: self.validate,
         }
     }
 }
@@ -80,7 +85,27 @@ impl SolverWithSolutionParsing for GlpkSolver {
             },
             _ => return Err("Incorrect solution format: No solution status found".to_string()),
         };
-        let mut result_lines = iter.skip(row + 7);
+        let header = match iter.nth(2) {
+            Some(Ok(l)) => l,
+            _ => return Err("Incorrect solution format: No row header found".to_string()),
+        };
+        // the marginals are only reported for problems without integer variables
+        let marginals = header.contains("Marginal");
+        let mut duals = HashMap::new();
+        let mut reduced_costs = HashMap::new();
+        let mut result_lines = iter.skip(1);
+        for _ in 0..row {
+            let line = match result_lines.next() {
+                Some(Ok(l)) => l,
+                _ => return Err("Incorrect solution format: Not all rows are present".to_string()),
+            };
+            if marginals {
+                if let Some(name) = line.split_whitespace().nth(1) {
+                    duals.insert(name.to_string(), read_marginal(&line)?);
+                }
+            }
+        }
+        let mut result_lines = result_lines.skip(3);
         for _ in 0..col {
             let line = match result_lines.next() {
                 Some(Ok(l)) => l,
@@ -92,9 +117,12 @@ impl SolverWithSolutionParsing for GlpkSolver {
             };
             let result_line: Vec<_> = line.split_whitespace().collect();
             if result_line.len() >= 4 {
-                match result_line[3].parse::<f32>() {
+                match result_line[3].parse::<f64>() {
                     Ok(n) => {
                         vars_value.insert(result_line[1].to_string(), n);
+                        if marginals {
+                            reduced_costs.insert(result_line[1].to_string(), read_marginal(&line)?);
+                        }
                     }
                     Err(e) => return Err(e.to_string()),
                 }
@@ -105,20 +133,68 @@ impl SolverWithSolutionParsing for GlpkSolver {
                 );
             }
         }
-        if let Some(p) = problem {
-            Ok( Solution::with_problem(status, vars_value, p) )
+        let solution = if let Some(p) = problem {
+            Solution::with_problem(status, vars_value, p)
         } else {
-            Ok( Solution::new(status, vars_value) )
+            Solution::new(status, vars_value)
+        };
+        Ok(solution.with_duals(duals, reduced_costs))
+    }
+}
+
+/// Last field of a row or column of the report, empty for the basic ones
+fn read_marginal(line: &str) -> Result<f64, String> {
+    match line.get(65..).map(str::trim) {
+        None | Some("") | Some("< eps") => Ok(0.0),
+        Some(value) => value.parse::<f64>().map_err(|e| e.to_string()),
+    }
+}
+
+impl WithValidation<GlpkSolver> for GlpkSolver {
+    fn validation(&self) -> bool {
+        self.validate
+    }
+    fn with_validation(&self, validate: bool) -> GlpkSolver {
+        GlpkSolver {
+            validate,
+            ..self.clone()
         }
     }
 }
 
+impl SolverWithSolutionPool for GlpkSolver {}
+
 impl SolverTrait for GlpkSolver {
     type P = LpProblem;
     fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, String> {
+        if self.validate {
+            problem.validate().check()?;
+        }
+        if problem.has_multi_objectives() {
+            return solve_lexicographic(self, problem);
+        }
+        if !problem.sos.is_empty() {
+            return Err(format!("Special ordered sets are not supported by the {} solver", self.name));
+        }
+        if problem.has_quadratic_objective() || problem.has_quadratic_constraints() {
+            return Err(format!("Quadratic objectives and constraints are not supported by the {} solver", self.name));
+        }
         let file_model = &format!("{}.lp", problem.unique_name);
 
-        match problem.write_lp(file_model) {
+        // Glpk does not read semi-continuous variables, indicator nor range constraints from LP files
+        let mut reformulated = None;
+        if problem.has_semi_continuous() {
+            reformulated = Some(problem.reformulate_semi_continuous()?);
+        }
+        if !problem.indicators.is_empty() {
+            reformulated = Some(reformulated.as_ref().unwrap_or(problem).reformulate_indicators()?);
+        }
+        if problem.has_ranges() {
+            reformulated = Some(reformulated.as_ref().unwrap_or(problem).reformulate_ranges());
+        }
+        let model = reformulated.as_ref().unwrap_or(problem);
+
+        match model.write_lp(file_model) {
             Ok(_) => {
                 let result = match Command::new(&self.command_name)
                     .arg("--lp")
@@ -130,6 +206,11 @@ impl SolverTrait for GlpkSolver {
                         Ok(r) => {
                             if r.status.success() {
                                 self.read_solution(&self.temp_solution_file, Some(problem))
+                                    .map(|solution| if reformulated.is_some() {
+                                        solution.for_problem(problem)
+                                    } else {
+                                        solution
+                                    })
                             } else {
                                 Err(r.status.to_string())
                             }
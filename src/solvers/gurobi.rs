@@ -4,17 +4,21 @@ use self::uuid::Uuid;
 use std::fs;
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufReader, BufRead};
+use std::io::{BufReader, BufRead, Write};
 use std::process::Command;
 
 use dsl::LpProblem;
+use format::json::{parse_json, Json};
 use format::lp_format::*;
-use solvers::{Status, SolverTrait, SolverWithSolutionParsing, Solution};
+use solvers::{Status, SolverTrait, SolverWithSolutionParsing, SolverWithSolutionPool, Solution, SolutionPool, WithValidation, WithInitialSolution, initial_values};
 
+#[derive(Debug, Clone)]
 pub struct GurobiSolver {
     name: String,
     command_name: String,
     temp_solution_file: String,
+    validate: bool,
+    initial_solution: Option<HashMap<String, f64>>,
 }
 
 impl GurobiSolver {
@@ -23,6 +27,8 @@ impl GurobiSolver {
             name: "Gurobi".to_string(),
             command_name: "gurobi_cl".to_string(),
             temp_solution_file: format!("{}.sol", Uuid::new_v4().to_string()),
+            validate: false,
+            initial_solution: None,
         }
     }
     pub fn command_name(&self, command_name: String) -> GurobiSolver {
@@ -30,8 +36,22 @@ impl GurobiSolver {
             name: self.name.clone(),
             command_name,
             temp_solution_file: self.temp_solution_file.clone(),
+            validate: self.validate,
+            initial_solution: self.initial_solution.clone(),
         }
     }
+
+    /// Write the initial solution for the variables of `problem` to `path` in the MST format
+    pub fn write_initial_solution(&self, path: &str, problem: &LpProblem) -> Result<(), String> {
+        let values = self.initial_solution.as_ref().ok_or("No initial solution")?;
+        let mut content = String::from("# MIP start\n");
+        for (name, value) in initial_values(values, &problem.variables()) {
+            content.push_str(&format!("{} {}\n", name, value));
+        }
+        File::create(path)
+            .and_then(|mut file| file.write_all(content.as_bytes()))
+            .map_err(|e| e.to_string())
+    }
 }
 
 impl SolverWithSolutionParsing for GurobiSolver {
@@ -52,7 +72,7 @@ impl SolverWithSolutionParsing for GurobiSolver {
 
                 let result_line: Vec<_> = l.split_whitespace().collect();
                 if result_line.len() == 2 {
-                    match result_line[1].parse::<f32>() {
+                    match result_line[1].parse::<f64>() {
                         Ok(n) => {
                             vars_value.insert(result_line[0].to_string(), n);
                         }
@@ -74,15 +94,112 @@ impl SolverWithSolutionParsing for GurobiSolver {
     }
 }
 
+impl WithValidation<GurobiSolver> for GurobiSolver {
+    fn validation(&self) -> bool {
+        self.validate
+    }
+    fn with_validation(&self, validate: bool) -> GurobiSolver {
+        GurobiSolver {
+            validate,
+            ..self.clone()
+        }
+    }
+}
+
+impl WithInitialSolution<GurobiSolver> for GurobiSolver {
+    fn initial_solution(&self) -> Option<&HashMap<String, f64>> {
+        self.initial_solution.as_ref()
+    }
+    fn with_initial_solution(&self, values: HashMap<String, f64>) -> GurobiSolver {
+        GurobiSolver {
+            initial_solution: Some(values),
+            ..self.clone()
+        }
+    }
+}
+
 impl SolverTrait for GurobiSolver {
     type P = LpProblem;
     fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, String> {
+        self.solve(problem, None).map(|mut solutions| solutions.remove(0))
+    }
+}
+
+impl SolverWithSolutionPool for GurobiSolver {
+    /// Solutions of the pool of Gurobi (`PoolSolutions`, with a systematic search of the best
+    /// solutions), read from a JSON result file
+    fn run_pool<'a>(&self, problem: &'a LpProblem, pool: &SolutionPool) -> Result<Vec<Solution<'a>>, String> {
+        let solutions = self.solve(problem, Some(pool))?;
+        pool.select(problem, solutions)
+    }
+}
+
+impl GurobiSolver {
+    /// Read the solutions of the pool from a result file in the JSON format of Gurobi, best first
+    pub fn read_pool<'a>(&self, path: &str, problem: &'a LpProblem) -> Result<Vec<Solution<'a>>, String> {
+        let content = fs::read_to_string(path).map_err(|_| "Cannot open file".to_string())?;
+        let _ = fs::remove_file(path);
+        let json = parse_json(&content)?;
+        let info = |key: &str| json.get("SolutionInfo").and_then(|info| info.get(key)).and_then(Json::as_f64);
+        let nb_solutions = info("SolCount").ok_or("Incorrect solution format")? as usize;
+        let status = match info("Status").map(|status| status as u32) {
+            Some(2) => Status::Optimal,
+            Some(3) | Some(4) => Status::Infeasible,
+            Some(5) => Status::Unbounded,
+            Some(_) if nb_solutions > 0 => Status::SubOptimal,
+            Some(_) => Status::NotSolved,
+            None => return Err("Incorrect solution format".to_string()),
+        };
+        let mut results: Vec<HashMap<String, f64>> = vec![HashMap::new(); nb_solutions];
+        for var in json.get("Vars").and_then(Json::as_array).unwrap_or(&[]) {
+            let name = var.get("VarName").and_then(Json::as_str).ok_or("Incorrect solution format")?;
+            // without several solutions, only the value of the best one is written
+            let values = match (var.get("Xn").and_then(Json::as_array), var.get("X")) {
+                (Some(values), _) => values,
+                (None, Some(value)) => std::slice::from_ref(value),
+                (None, None) => return Err("Incorrect solution format".to_string()),
+            };
+            for (values, value) in results.iter_mut().zip(values) {
+                values.insert(name.to_string(), value.as_f64().ok_or("Incorrect solution format")?);
+            }
+        }
+        if results.is_empty() {
+            return Ok(vec![Solution::with_problem(status, HashMap::new(), problem)]);
+        }
+        Ok(results.into_iter()
+            .map(|values| Solution::with_problem(status.clone(), values, problem))
+            .collect())
+    }
+
+    /// Solve `problem` and read the best solution, or the solutions of the pool with `pool`
+    fn solve<'a>(&self, problem: &'a LpProblem, pool: Option<&SolutionPool>) -> Result<Vec<Solution<'a>>, String> {
+        if self.validate {
+            problem.validate().check()?;
+        }
         let file_model = &format!("{}.lp", problem.unique_name);
+        let file_start = format!("{}.mst", problem.unique_name);
+        let file_pool = format!("{}.json", self.temp_solution_file);
+        let mut params = Vec::new();
+        if self.initial_solution.is_some() {
+            self.write_initial_solution(&file_start, problem)?;
+            params.push(format!("InputFile={}", file_start));
+        }
+        if let Some(pool) = pool {
+            params.push(format!("ResultFile={}", file_pool));
+            params.push(format!("PoolSolutions={}", pool.max_solutions));
+            // systematic search of the best solutions
+            params.push("PoolSearchMode=2".to_string());
+            if let (Some(gap), false) = (pool.gap, problem.has_multi_objectives()) {
+                params.push(format!("PoolGap={}", gap));
+            }
+        } else {
+            params.push(format!("ResultFile={}", self.temp_solution_file));
+        }
 
         match problem.write_lp(file_model) {
             Ok(_) => {
                 let result = match Command::new(&self.command_name)
-                    .arg(format!("ResultFile={}", self.temp_solution_file))
+                    .args(&params)
                     .arg(file_model)
                     .output()
                     {
@@ -95,15 +212,19 @@ impl SolverTrait for GurobiSolver {
                             } else if result.contains("infesible") {
                                 status = Status::Infeasible;
                             }
-                            if r.status.success() {
-                                self.read_solution(&self.temp_solution_file, Some(problem)).map(|solution| Solution {status, ..solution.clone()} )
-                            } else {
+                            if !r.status.success() {
                                 Err(r.status.to_string())
+                            } else if pool.is_some() {
+                                self.read_pool(&file_pool, problem)
+                            } else {
+                                self.read_solution(&self.temp_solution_file, Some(problem))
+                                    .map(|solution| vec![Solution {status, ..solution}])
                             }
                         }
                         Err(_) => Err(format!("Error running the {} solver", self.name)),
                     };
                 let _ = fs::remove_file(&file_model);
+                let _ = fs::remove_file(&file_start);
 
                 result
             }
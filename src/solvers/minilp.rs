@@ -1,8 +1,8 @@
-use dsl::{LpObjective, LpProblem, LpConstraint, LpExpression, Constraint, LpExprNode, LpContinuous};
+use dsl::{LpObjective, LpProblem, LpConstraint, LpExpression, Constraint, LpExprNode, LpContinuous, LpInteger, LpBinary, LpVariables};
 use std::collections::HashMap;
-use solvers::{SolverTrait, Solution, Status};
+use std::time::{Duration, Instant};
+use solvers::{SolverTrait, SolverWithSolutionPool, Solution, Status, WithMaxSeconds, WithValidation, solve_lexicographic};
 use dsl::LpExprNode::LitVal;
-use dsl::LpExprOp::{Multiplication, Addition, Subtraction};
 
 fn direction_to_minilp(objective: &LpObjective) -> minilp::OptimizationDirection {
     match objective {
@@ -16,7 +16,7 @@ fn add_constraint_to_minilp(
     variables: &mut HashMap<String, minilp::Variable>,
     pb: &mut minilp::Problem,
 ) -> Result<(), String> {
-    let LpConstraint(expr, op, constant_arena) = constraint.clone();
+    let LpConstraint(expr, op, constant_arena, _) = constraint.clone();
     let constant = if let &LitVal(c) = constant_arena.get_root_expr_ref() { c } else {
         return Err("not properly simplified".into());
     };
@@ -24,33 +24,40 @@ fn add_constraint_to_minilp(
     let mut expr = minilp::LinearExpr::empty();
     for (name, coefficient) in expr_variables.0 {
         let var = variables.entry(name).or_insert_with(|| {
-            pb.add_var(0., (f64::NEG_INFINITY, f64::INFINITY))
-        }).clone();
-        expr.add(var, coefficient.coefficient.into());
+            pb.add_var(0., (coefficient.min, coefficient.max))
+        });
+        expr.add(*var, coefficient.coefficient);
+    }
+    for (op, rhs) in comparison_to_minilp(op, constant) {
+        pb.add_constraint(expr.clone(), op, rhs);
     }
-    let op = comparison_to_minilp(op);
-    pb.add_constraint(expr, op, f64::from(constant));
     Ok(())
 }
 
-fn comparison_to_minilp(op: Constraint) -> minilp::ComparisonOp {
+/// minilp has no range constraint: a range gives two comparisons
+fn comparison_to_minilp(op: Constraint, constant: f64) -> Vec<(minilp::ComparisonOp, f64)> {
     match op {
-        Constraint::GreaterOrEqual => minilp::ComparisonOp::Ge,
-        Constraint::LessOrEqual => minilp::ComparisonOp::Le,
-        Constraint::Equal => minilp::ComparisonOp::Eq
+        Constraint::GreaterOrEqual => vec![(minilp::ComparisonOp::Ge, constant)],
+        Constraint::LessOrEqual => vec![(minilp::ComparisonOp::Le, constant)],
+        Constraint::Equal => vec![(minilp::ComparisonOp::Eq, constant)],
+        Constraint::Range(width) => vec![
+            (minilp::ComparisonOp::Ge, constant - width),
+            (minilp::ComparisonOp::Le, constant),
+        ],
     }
 }
 
 #[derive(Debug, PartialEq)]
 struct VarWithCoeff {
-    coefficient: f32,
-    min: f32,
-    max: f32,
+    coefficient: f64,
+    min: f64,
+    max: f64,
+    integer: bool,
 }
 
 impl Default for VarWithCoeff {
     fn default() -> Self {
-        VarWithCoeff { coefficient: 0., min: f32::NEG_INFINITY, max: f32::INFINITY }
+        VarWithCoeff { coefficient: 0., min: f64::NEG_INFINITY, max: f64::INFINITY, integer: false }
     }
 }
 
@@ -58,10 +65,24 @@ impl Default for VarWithCoeff {
 struct VarList(HashMap<String, VarWithCoeff>);
 
 impl VarList {
-    fn add(&mut self, var: LpContinuous, coefficient: f32) {
+    fn add(&mut self, var: LpContinuous, coefficient: f64) {
         let LpContinuous { name, lower_bound, upper_bound } = var;
+        self.add_with_bounds(name, lower_bound, upper_bound, false, coefficient);
+    }
+
+    fn add_integer(&mut self, var: LpInteger, coefficient: f64) {
+        let LpInteger { name, lower_bound, upper_bound } = var;
+        self.add_with_bounds(name, lower_bound, upper_bound, true, coefficient);
+    }
+
+    fn add_binary(&mut self, var: LpBinary, coefficient: f64) {
+        self.add_with_bounds(var.name, Some(0.), Some(1.), true, coefficient);
+    }
+
+    fn add_with_bounds(&mut self, name: String, lower_bound: Option<f64>, upper_bound: Option<f64>, integer: bool, coefficient: f64) {
         let prev = self.0.entry(name).or_default();
         prev.coefficient += coefficient;
+        prev.integer |= integer;
         if let Some(lower) = lower_bound {
             prev.min = prev.min.max(lower);
         }
@@ -72,29 +93,15 @@ impl VarList {
 }
 
 fn decompose_expression(
-    mut expr: LpExpression,
+    expr: LpExpression,
 ) -> Result<VarList, String> {
-    expr.simplify();
+    let (terms, _) = expr.linear_terms()?;
     let mut decomposed = VarList::default();
-    let mut idxs = vec![(1., expr.get_root_index())];
-    while let Some((factor, idx)) = idxs.pop() {
-        match expr.expr_ref_at(idx) {
-            LpExprNode::ConsCont(var) => { decomposed.add(var.clone(), factor) }
-            &LpExprNode::LpCompExpr(Multiplication, lhs, rhs) => {
-                if let &LpExprNode::LitVal(lit) = expr.expr_ref_at(lhs) {
-                    idxs.push((factor * lit, rhs))
-                } else {
-                    return Err(format!("Non-simplified multiplication: {:?}", expr.expr_ref_at(idx)));
-                }
-            }
-            &LpExprNode::LpCompExpr(Addition, lhs, rhs) => {
-                idxs.push((factor, lhs));
-                idxs.push((factor, rhs));
-            }
-            &LpExprNode::LpCompExpr(Subtraction, lhs, rhs) => {
-                idxs.push((factor, lhs));
-                idxs.push((-factor, rhs));
-            }
+    for (var, coefficient) in terms {
+        match var {
+            LpExprNode::ConsCont(var) => { decomposed.add(var, coefficient) }
+            LpExprNode::ConsInt(var) => { decomposed.add_integer(var, coefficient) }
+            LpExprNode::ConsBin(var) => { decomposed.add_binary(var, coefficient) }
             x => return Err(format!("Unsupported expression: {:?}", x))
         }
     }
@@ -109,17 +116,24 @@ fn add_objective_to_minilp(
 ) -> Result<HashMap<String, minilp::Variable>, String> {
     let vars = decompose_expression(objective)?;
     Ok(vars.0.into_iter()
-        .map(|(name, VarWithCoeff { coefficient, min, max })| {
+        .map(|(name, VarWithCoeff { coefficient, min, max, .. })| {
             let var = pb.add_var(
-                coefficient.into(),
-                (min.into(), max.into()),
+                coefficient,
+                (min, max),
             );
             (name, var)
         }).collect()
     )
 }
 
-fn problem_to_minilp(pb: &LpProblem) -> Result<(minilp::Problem, Vec<Option<String>>), String> {
+/// minilp problem, names of its variables and the integer ones
+struct MiniLpModel {
+    problem: minilp::Problem,
+    variable_names: Vec<Option<String>>,
+    integers: Vec<minilp::Variable>,
+}
+
+fn problem_to_minilp(pb: &LpProblem, variables: &LpVariables) -> Result<MiniLpModel, String> {
     let objective = direction_to_minilp(&pb.objective_type);
     let mut minilp_pb = minilp::Problem::new(objective);
     let objective = pb.obj_expr_arena.clone().ok_or("Missing objective")?;
@@ -131,48 +145,186 @@ fn problem_to_minilp(pb: &LpProblem) -> Result<(minilp::Problem, Vec<Option<Stri
             &mut minilp_pb,
         )?;
     }
+    let mut integers: Vec<minilp::Variable> = variables.iter()
+        .filter(|var| var.is_integer())
+        .filter_map(|var| minilp_variables.get(var.name()).cloned())
+        .collect();
+    integers.sort_by_key(|var| var.idx());
     let mut ordered_vars = vec![None; minilp_variables.len()];
     for (name, var) in minilp_variables {
         ordered_vars[var.idx()] = Some(name);
     }
-    Ok((minilp_pb, ordered_vars))
+    Ok(MiniLpModel { problem: minilp_pb, variable_names: ordered_vars, integers })
+}
+
+/// Depth-first branch and bound on the integer variables, using the relaxations solved by minilp.
+///
+/// Returns the best integer solution with `Status::Optimal` if the search completes, and with
+/// `Status::SubOptimal` if it stops after `max_nodes` relaxations or at the `deadline`.
+/// Without integer solution, the status is `Status::Infeasible` or `Status::NotSolved`.
+fn branch_and_bound(
+    problem: &minilp::Problem,
+    integers: &[minilp::Variable],
+    direction: &LpObjective,
+    max_nodes: usize,
+    deadline: Option<Instant>,
+) -> Result<(Status, Option<minilp::Solution>), minilp::Error> {
+    const INTEGRALITY_TOLERANCE: f64 = 1e-6;
+    let improves = |candidate: &minilp::Solution, best: &Option<minilp::Solution>| match best {
+        None => true,
+        Some(best) => match direction {
+            LpObjective::Maximize => candidate.objective() > best.objective() + INTEGRALITY_TOLERANCE,
+            LpObjective::Minimize => candidate.objective() < best.objective() - INTEGRALITY_TOLERANCE,
+        },
+    };
+
+    let mut best: Option<minilp::Solution> = None;
+    let mut nodes = vec![problem.solve()?];
+    let mut explored = 0;
+    while let Some(node) = nodes.pop() {
+        if explored >= max_nodes || deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            let status = if best.is_some() { Status::SubOptimal } else { Status::NotSolved };
+            return Ok((status, best));
+        }
+        explored += 1;
+        if !improves(&node, &best) {
+            continue;
+        }
+        let fractional = integers.iter()
+            .map(|&var| (var, node[var]))
+            .find(|(_, value)| (value - value.round()).abs() > INTEGRALITY_TOLERANCE);
+        match fractional {
+            None => best = Some(node),
+            Some((var, value)) => {
+                let down = node.clone().add_constraint([(var, 1.0)], minilp::ComparisonOp::Le, value.floor());
+                let up = node.add_constraint([(var, 1.0)], minilp::ComparisonOp::Ge, value.ceil());
+                nodes.extend(down.into_iter().chain(up));
+            }
+        }
+    }
+    match best {
+        Some(best) => Ok((Status::Optimal, Some(best))),
+        None => Ok((Status::Infeasible, None)),
+    }
 }
 
-pub struct MiniLpSolver;
+/// Solver calling the pure Rust [minilp](https://docs.rs/minilp) crate, with a branch and
+/// bound for the integer variables. The search is limited to 100 000 relaxations by default
+/// (see `with_max_nodes`) and to `with_max_seconds` if set: the best solution found is then
+/// returned as `Status::SubOptimal`, or `Status::NotSolved` if there is none.
+#[derive(Debug, Clone)]
+pub struct MiniLpSolver {
+    validate: bool,
+    max_nodes: usize,
+    seconds: Option<u32>,
+}
+
+impl Default for MiniLpSolver {
+    fn default() -> Self {
+        MiniLpSolver {
+            validate: false,
+            max_nodes: 100_000,
+            seconds: None,
+        }
+    }
+}
 
 impl MiniLpSolver {
-    pub fn new() -> Self { Self }
+    pub fn new() -> Self { Self::default() }
+
+    /// Largest number of relaxations solved by the branch and bound
+    pub fn with_max_nodes(&self, max_nodes: usize) -> MiniLpSolver {
+        MiniLpSolver {
+            max_nodes,
+            ..self.clone()
+        }
+    }
+}
+
+impl WithValidation<MiniLpSolver> for MiniLpSolver {
+    fn validation(&self) -> bool {
+        self.validate
+    }
+    fn with_validation(&self, validate: bool) -> MiniLpSolver {
+        MiniLpSolver {
+            validate,
+            ..self.clone()
+        }
+    }
 }
 
+impl WithMaxSeconds<MiniLpSolver> for MiniLpSolver {
+    fn max_seconds(&self) -> Option<u32> {
+        self.seconds
+    }
+    fn with_max_seconds(&self, seconds: u32) -> MiniLpSolver {
+        MiniLpSolver {
+            seconds: Some(seconds),
+            ..self.clone()
+        }
+    }
+}
+
+impl SolverWithSolutionPool for MiniLpSolver {}
+
 impl SolverTrait for MiniLpSolver {
     type P = LpProblem;
 
     fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, String> {
-        let (minilp_pb, variable_names) = problem_to_minilp(problem)?;
-        let minilp_result = minilp_pb.solve();
-        solution_from_minilp(minilp_result, variable_names)
+        if self.validate {
+            problem.validate().check()?;
+        }
+        if problem.has_multi_objectives() {
+            return solve_lexicographic(self, problem);
+        }
+        if !problem.sos.is_empty() {
+            return Err("Special ordered sets are not supported by MiniLpSolver".to_string());
+        }
+        if problem.has_quadratic_objective() || problem.has_quadratic_constraints() {
+            return Err("Quadratic objectives and constraints are not supported by MiniLpSolver".to_string());
+        }
+        if !problem.indicators.is_empty() {
+            let reformulated = problem.reformulate_indicators()?;
+            let solution = self.run(&reformulated)?;
+            return Ok(Solution::with_problem(solution.status, solution.results, problem));
+        }
+        let variables = problem.variables();
+        if variables.iter().any(|var| var.is_semi_continuous()) {
+            let reformulated = problem.reformulate_semi_continuous()?;
+            return Ok(self.run(&reformulated)?.for_problem(problem));
+        }
+        let MiniLpModel { problem: minilp_pb, variable_names, integers } = problem_to_minilp(problem, &variables)?;
+        let deadline = self.seconds.map(|seconds| Instant::now() + Duration::from_secs(u64::from(seconds)));
+        let minilp_result = branch_and_bound(&minilp_pb, &integers, &problem.objective_type, self.max_nodes, deadline);
+        let solution = solution_from_minilp(minilp_result, variable_names, &integers)?;
+        Ok(Solution::with_problem(solution.status, solution.results, problem))
     }
 }
 
 fn solution_from_minilp(
-    result: Result<minilp::Solution, minilp::Error>,
+    result: Result<(Status, Option<minilp::Solution>), minilp::Error>,
     mut variable_names: Vec<Option<String>>,
+    integers: &[minilp::Variable],
 ) -> Result<Solution<'static>, String> {
     match result {
-        Ok(solution) => {
-            let results: Option<HashMap<String, f32>> = solution.iter()
+        Ok((status, Some(solution))) => {
+            let results: Option<HashMap<String, f64>> = solution.iter()
                 .map(|(var, &value)| {
+                    let value = if integers.contains(&var) { value.round() } else { value };
                     std::mem::take(&mut variable_names[var.idx()]).map(|name| {
-                        (name, value as f32)
+                        (name, value)
                     })
                 })
                 .collect();
             if let Some(results) = results {
-                Ok(Solution::new(Status::Optimal, results))
+                Ok(Solution::new(status, results))
             } else {
                 Err("missing variable name".into())
             }
         }
+        Ok((status, None)) => {
+            Ok(Solution::new(status, HashMap::new()))
+        }
         Err(minilp::Error::Unbounded) => {
             Ok(Solution::new(Status::Unbounded, HashMap::new()))
         }
@@ -206,12 +358,10 @@ fn test_solve() {
     problem += (500 * a - 1000 * b).ge(10000);
     problem += (a).le(b);
 
-    let expected: HashMap<String, f32> = vec![
-        ("a".into(), -20.),
-        ("b".into(), -20.)
-    ].into_iter().collect();
     let actual = MiniLpSolver::new().run(&problem).expect("could not solve").results;
-    assert_eq!(actual, expected);
+    assert_eq!(actual.len(), 2);
+    assert!(::util::is_zero(actual["a"] + 20.));
+    assert!(::util::is_zero(actual["b"] + 20.));
 }
 
 #[test]
@@ -226,4 +376,416 @@ fn decompose_large() {
     let sum = lp_sum(&vars);
     let vars = decompose_expression(sum).expect("decompose failed");
     assert_eq!(vars.0.keys().len(), count);
-}
\ No newline at end of file
+}
+#[test]
+fn test_solve_integers() {
+    use dsl::operations::LpOperations;
+    use dsl::BoundableLp;
+    let ref a = LpInteger::new("a").lower_bound(0.);
+    let ref b = LpBinary::new("b");
+
+    let mut problem = LpProblem::new("Integers", LpObjective::Maximize);
+    problem += 3 * a + 2 * b;
+    problem += (2 * a + 2 * b).le(7);
+
+    let actual = MiniLpSolver::new().run(&problem).expect("could not solve").results;
+    let expected: HashMap<String, f64> = vec![
+        ("a".into(), 3.),
+        ("b".into(), 0.)
+    ].into_iter().collect();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_solve_semi_continuous() {
+    use dsl::operations::LpOperations;
+    use dsl::{BoundableLp, LpSemiContinuous};
+    let ref x = LpSemiContinuous::new("x").lower_bound(50.).upper_bound(200.);
+    let ref y = LpContinuous::new("y").lower_bound(0.);
+
+    // producing 10 with x would force x >= 50: cheaper to use y only
+    let mut problem = LpProblem::new("Semi", LpObjective::Minimize);
+    problem += x + 3 * y;
+    problem += (x + y).ge(10);
+
+    let actual = MiniLpSolver::new().run(&problem).expect("could not solve").results;
+    let expected: HashMap<String, f64> = vec![
+        ("x".into(), 0.),
+        ("y".into(), 10.)
+    ].into_iter().collect();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_reject_sos() {
+    use dsl::operations::LpOperations;
+    use dsl::{LpSos, SosType};
+    let ref a = LpContinuous::new("a");
+    let ref b = LpContinuous::new("b");
+
+    let mut problem = LpProblem::new("Sos", LpObjective::Maximize);
+    problem += a + b;
+    problem += (a + b).le(1);
+    problem += LpSos::new("ab", SosType::Sos1, vec![(a, 1.), (b, 2.)]);
+
+    assert!(MiniLpSolver::new().run(&problem).is_err());
+}
+
+#[test]
+fn test_solve_range() {
+    use dsl::LpRangeOperations;
+    let ref x = LpContinuous::new("x");
+    let ref y = LpContinuous::new("y");
+    let mut problem = LpProblem::new("Range", LpObjective::Maximize);
+    problem += x - y;
+    problem += (x + y).between(2, 3);
+    problem += (x - 2 * y).between(-1, 1);
+    let actual = MiniLpSolver::new().run(&problem).expect("could not solve").results;
+    // x + y = 3 and x - 2y = 1
+    assert!(::util::is_zero(actual["x"] - 7. / 3.));
+    assert!(::util::is_zero(actual["y"] - 2. / 3.));
+}
+
+#[test]
+fn test_validation() {
+    use dsl::operations::LpOperations;
+    use dsl::BoundableLp;
+    let ref x = LpContinuous::new("x").lower_bound(1.).upper_bound(0.);
+    let mut problem = LpProblem::new("Invalid", LpObjective::Maximize);
+    problem += x;
+    problem += x.le(5);
+    let error = MiniLpSolver::new().with_validation(true).run(&problem).unwrap_err();
+    assert!(error.contains("lower bound 1 greater than its upper bound 0"));
+}
+
+#[test]
+fn test_reject_quadratic() {
+    use dsl::operations::LpOperations;
+    let ref x = LpContinuous::new("x");
+    let ref y = LpContinuous::new("y");
+    let mut problem = LpProblem::new("Quadratic", LpObjective::Minimize);
+    problem += x * y;
+    problem += (x + y).ge(1);
+    assert!(MiniLpSolver::new().run(&problem).is_err());
+}
+
+#[test]
+fn test_solve_indicator() {
+    use dsl::operations::LpOperations;
+    use dsl::{BoundableLp, LpIndicator};
+    let ref x = LpContinuous::new("x").lower_bound(0.).upper_bound(100.);
+    let ref open = LpBinary::new("open");
+
+    // opening costs 10, each unit brings 1 but at most 5 units can be sold
+    let mut problem = LpProblem::new("Indicator", LpObjective::Maximize);
+    problem += x - 10 * open;
+    problem += x.le(5);
+    problem += LpIndicator::new(open, false, x.le(0));
+
+    let actual = MiniLpSolver::new().run(&problem).expect("could not solve").results;
+    assert_eq!(actual["open"], 0.);
+    assert!(::util::is_zero(actual["x"]));
+}
+
+#[test]
+fn test_solve_piecewise() {
+    use dsl::operations::LpOperations;
+    use dsl::{LpPiecewise, PiecewiseFormulation};
+    // revenue with decreasing marginal gains: 5, 3 then 1 per unit
+    let breakpoints = vec![(0.0, 0.0), (10.0, 50.0), (30.0, 110.0), (60.0, 140.0)];
+
+    for formulation in vec![PiecewiseFormulation::Incremental, PiecewiseFormulation::Convex] {
+        let ref x = LpContinuous::new("x");
+        let mut problem = LpProblem::new("Piecewise", LpObjective::Maximize);
+        let revenue = LpPiecewise::new("revenue", breakpoints.clone())
+            .with_formulation(formulation)
+            .apply(&mut problem, x)
+            .expect("valid breakpoints");
+        // each unit costs 2: producing is worth it until the end of the second segment
+        problem += revenue - 2 * x;
+        problem += x.le(50);
+
+        let actual = MiniLpSolver::new().run(&problem).expect("could not solve").results;
+        assert!(::util::is_zero(actual["x"] - 30.));
+    }
+}
+
+#[test]
+fn test_solve_linearization() {
+    use dsl::operations::LpOperations;
+    use dsl::{lp_abs, lp_max, lp_min, lp_product, BoundableLp};
+    let ref x = LpContinuous::new("x").lower_bound(0).upper_bound(10);
+    let ref y = LpContinuous::new("y").lower_bound(0).upper_bound(10);
+
+    let mut problem = LpProblem::new("Abs", LpObjective::Maximize);
+    let distance = lp_abs(&mut problem, "distance", x - y).unwrap();
+    problem += distance - 0.5 * x;
+    let actual = MiniLpSolver::new().run(&problem).expect("could not solve").results;
+    assert!(::util::is_zero(actual["x"]));
+    assert!(::util::is_zero(actual["y"] - 10.));
+
+    let mut problem = LpProblem::new("Max", LpObjective::Minimize);
+    let makespan = lp_max(&mut problem, "makespan", &[x + 2, y + 3]).unwrap();
+    problem += makespan;
+    problem += (x + y).ge(12);
+    let actual = MiniLpSolver::new().run(&problem).expect("could not solve").results;
+    assert!(::util::is_zero(actual["makespan"] - 8.5));
+
+    let mut problem = LpProblem::new("Min", LpObjective::Maximize);
+    let smallest = lp_min(&mut problem, "smallest", &[x + 0, 10 - x]).unwrap();
+    problem += smallest;
+    let actual = MiniLpSolver::new().run(&problem).expect("could not solve").results;
+    assert!(::util::is_zero(actual["x"] - 5.));
+
+    let ref a = LpBinary::new("a");
+    let ref b = LpBinary::new("b");
+    let mut problem = LpProblem::new("Product", LpObjective::Maximize);
+    let both = lp_product(&mut problem, "both", a, b).unwrap();
+    problem += 3 * both - a - b;
+    let actual = MiniLpSolver::new().run(&problem).expect("could not solve").results;
+    assert_eq!((actual["a"], actual["b"]), (1., 1.));
+    assert!(::util::is_zero(actual["both"] - 1.));
+}
+
+#[test]
+fn test_what_if() {
+    use dsl::operations::LpOperations;
+    let ref x = LpContinuous::new("x");
+    let ref y = LpContinuous::new("y");
+
+    let mut problem = LpProblem::new("What if", LpObjective::Maximize);
+    problem += 3 * x + 2 * y;
+    problem += (x + y).le(4).with_name("capacity");
+    problem += (x + 3 * y).le(6);
+    problem += x.le(3);
+    let solver = MiniLpSolver::new();
+    assert_eq!(solver.run(&problem).unwrap().results["x"], 3.);
+
+    problem.set_rhs("capacity", 3.5).unwrap();
+    problem.set_objective_coefficient(y, 4.0).unwrap();
+    problem.remove_constraint("c3");
+    let actual = solver.run(&problem).expect("could not solve").results;
+    assert!(::util::is_zero(actual["x"] - 2.25));
+    assert!(::util::is_zero(actual["y"] - 1.25));
+}
+
+#[test]
+fn test_solve_columns() {
+    use dsl::operations::LpOperations;
+    use dsl::{BoundableLp, LpColumn};
+    // cover the demand of two items with cutting patterns
+    let ref first = LpContinuous::new("first").lower_bound(0);
+    let ref second = LpContinuous::new("second").lower_bound(0);
+    let mut problem = LpProblem::new("Patterns", LpObjective::Minimize);
+    problem += first + second;
+    problem += first.ge(4).with_name("item_0");
+    problem += second.ge(6).with_name("item_1");
+    let solution = MiniLpSolver::new().run(&problem).expect("could not solve");
+    assert!(::util::is_zero(solution.results["first"] + solution.results["second"] - 10.0));
+
+    // a pattern cutting one item of each kind
+    let ref both = LpContinuous::new("both").lower_bound(0);
+    let column = LpColumn::new(both).with_objective(0.9).with_coefficient("item_0", 1.0).with_coefficient("item_1", 1.0);
+    problem.add_column(column).unwrap();
+    let solution = MiniLpSolver::new().run(&problem).expect("could not solve");
+    assert_eq!(solution.status, Status::Optimal);
+    assert!(::util::is_zero(solution.results["both"] - 6.0));
+    assert!(::util::is_zero(solution.results["first"] + solution.results["second"]));
+}
+
+#[test]
+fn test_constraint_activity() {
+    use dsl::operations::LpOperations;
+    use dsl::{BoundableLp, LpRangeOperations};
+    let ref a = LpContinuous::new("a").lower_bound(0);
+    let ref b = LpContinuous::new("b").lower_bound(0);
+    let mut problem = LpProblem::new("Utilization", LpObjective::Maximize);
+    problem += 3 * a + 2 * b;
+    problem += (a + b).le(4).with_name("machine");
+    problem += (a + 3 * b).le(8).with_name("labor");
+    problem += a.between(1, 3).with_name("orders");
+    problem += b.ge(0.5);
+
+    let solution = MiniLpSolver::new().run(&problem).expect("could not solve");
+    let activities = solution.constraint_activities().unwrap();
+    let ids: Vec<&str> = activities.iter().map(|activity| activity.id.as_str()).collect();
+    assert_eq!(ids, vec!["machine", "labor", "orders", "c4"]);
+    let binding: Vec<bool> = activities.iter().map(|activity| activity.is_binding()).collect();
+    assert_eq!(binding, vec![true, false, true, false]);
+    let orders = &activities[2];
+    assert_eq!((orders.lower, orders.upper), (Some(1.0), Some(3.0)));
+    assert!(::util::is_zero(orders.activity - 3.0));
+    assert!(::util::is_zero(activities[1].activity - 6.0));
+    assert!(::util::is_zero(activities[1].slack - 2.0));
+    assert!(solution.constraint_activity("unknown").is_err());
+}
+
+#[test]
+fn test_owned_solution() {
+    use dsl::operations::LpOperations;
+    use dsl::BoundableLp;
+    use solvers::{OwnedSolution, VariableType};
+    let ref a = LpInteger::new("a").lower_bound(0);
+    let ref b = LpBinary::new("b");
+    let ref c = LpContinuous::new("c").lower_bound(0).upper_bound(1.5);
+    let mut problem = LpProblem::new("Owned", LpObjective::Maximize);
+    problem += 2 * a + 3 * b + c;
+    problem += (a + b + c).le(5.5);
+
+    let owned: OwnedSolution = {
+        let solution = MiniLpSolver::new().run(&problem).expect("could not solve");
+        solution.into()
+    };
+    drop(problem);
+    assert_eq!(owned.status, Status::Optimal);
+    assert_eq!(owned.get_int(a), 4);
+    assert!(owned.get_bool(b));
+    assert!(::util::is_zero(owned.get_float(c) - 0.5));
+    assert!(::util::is_zero(owned.eval().unwrap() - 11.5));
+    assert_eq!(owned.variable_type("a"), Some(VariableType::Integer));
+    assert_eq!(owned.variable_type("b"), Some(VariableType::Binary));
+    assert_eq!(owned.variable_type("d"), None);
+}
+
+#[test]
+fn test_solution_pool() {
+    use dsl::operations::LpOperations;
+    use dsl::{BoundableLp, LpMultiObjective};
+    use solvers::{SolutionPool, SolverWithSolutionPool};
+    let ref a = LpBinary::new("a");
+    let ref b = LpBinary::new("b");
+    let ref c = LpBinary::new("c");
+    let mut problem = LpProblem::new("Pick", LpObjective::Maximize);
+    problem += 5 * a + 4 * b + 3 * c;
+    problem += (a + b + c).le(2);
+
+    let solver = MiniLpSolver::new();
+    let solutions = solver.run_pool(&problem, &SolutionPool::new(3)).expect("could not solve");
+    let values: Vec<f64> = solutions.iter().map(|solution| solution.eval().unwrap().round()).collect();
+    assert_eq!(values, vec![9.0, 8.0, 7.0]);
+    assert!(solutions[1].get_bool(a) && solutions[1].get_bool(c) && !solutions[1].get_bool(b));
+    let statuses: Vec<Status> = solutions.iter().map(|solution| solution.status.clone()).collect();
+    assert_eq!(statuses, vec![Status::Optimal, Status::SubOptimal, Status::SubOptimal]);
+    assert!(solutions[2].constraint("no_good_1").is_none());
+
+    let solutions = solver.run_pool(&problem, &SolutionPool::new(5).with_gap(0.15)).expect("could not solve");
+    assert_eq!(solutions.len(), 2);
+
+    // all the assignments of the binaries
+    let solutions = solver.run_pool(&problem, &SolutionPool::new(10)).expect("could not solve");
+    assert_eq!(solutions.len(), 7);
+
+    let ref x = LpContinuous::new("x").upper_bound(4);
+    let mut continuous = LpProblem::new("Continuous", LpObjective::Maximize);
+    continuous += x;
+    assert_eq!(solver.run_pool(&continuous, &SolutionPool::new(3)).expect("could not solve").len(), 1);
+
+    // the number of picks first, then the value
+    let mut problem = LpProblem::new("Pick", LpObjective::Maximize);
+    problem += LpMultiObjective::new("count", a + b + c).with_priority(2);
+    problem += LpMultiObjective::new("value", 5 * a + 4 * b + 3 * c).with_priority(1);
+    problem += (a + b + c).le(2);
+    let solutions = solver.run_pool(&problem, &SolutionPool::new(4)).expect("could not solve");
+    let picks: Vec<(bool, bool, bool)> = solutions.iter()
+        .map(|solution| (solution.get_bool(a), solution.get_bool(b), solution.get_bool(c)))
+        .collect();
+    assert_eq!(picks, vec![(true, true, false), (true, false, true), (false, true, true), (true, false, false)]);
+    let statuses: Vec<Status> = solutions.iter().map(|solution| solution.status.clone()).collect();
+    assert_eq!(statuses, vec![Status::Optimal, Status::SubOptimal, Status::SubOptimal, Status::SubOptimal]);
+    // the gap applies to each level: 7 is more than 20% away from 9
+    let solutions = solver.run_pool(&problem, &SolutionPool::new(4).with_gap(0.2)).expect("could not solve");
+    assert_eq!(solutions.len(), 2);
+}
+
+#[test]
+fn test_solve_multi_objectives() {
+    use dsl::operations::LpOperations;
+    use dsl::{BoundableLp, LpMultiObjective};
+    let ref x = LpContinuous::new("x").lower_bound(0).upper_bound(10);
+    let ref y = LpContinuous::new("y").lower_bound(0).upper_bound(10);
+
+    for &(tolerance, expected_x, expected_y) in &[(0.0, 10.0, 2.0), (2.0, 8.0, 4.0)] {
+        let mut problem = LpProblem::new("Lexicographic", LpObjective::Maximize);
+        problem += LpMultiObjective::new("first", x).with_priority(2).with_abs_tol(tolerance);
+        problem += LpMultiObjective::new("second", y).with_priority(1);
+        problem += (x + y).le(12);
+
+        let solution = MiniLpSolver::new().run(&problem).expect("could not solve");
+        assert_eq!(solution.status, Status::Optimal);
+        assert!(::util::is_zero(solution.results["x"] - expected_x));
+        assert!(::util::is_zero(solution.results["y"] - expected_y));
+    }
+}
+
+#[test]
+fn test_pareto_front() {
+    use dsl::operations::LpOperations;
+    use dsl::BoundableLp;
+    use solvers::EpsilonConstraint;
+    let ref cost = LpContinuous::new("cost").lower_bound(2).upper_bound(8);
+    let ref emissions = LpContinuous::new("emissions").lower_bound(2).upper_bound(8);
+    let mut problem = LpProblem::new("Plan", LpObjective::Minimize);
+    problem += (cost + emissions).equal(10);
+
+    let front = EpsilonConstraint::new(cost)
+        .with_objective(emissions, 3)
+        .run(&MiniLpSolver::new(), &problem)
+        .expect("could not solve");
+    let expected = [[2.0, 8.0], [4.0, 6.0], [6.0, 4.0], [8.0, 2.0]];
+    assert_eq!(front.len(), expected.len());
+    for (point, values) in front.iter().zip(&expected) {
+        assert!(::util::is_zero(point.objectives[0] - values[0]));
+        assert!(::util::is_zero(point.objectives[1] - values[1]));
+        assert!(::util::is_zero(point.solution.results["emissions"] - values[1]));
+    }
+}
+
+#[test]
+fn test_solve_logic() {
+    use dsl::operations::LpOperations;
+    use dsl::{lp_and, lp_or, lp_xor, lp_not};
+    let ref a = LpBinary::new("a");
+    let ref b = LpBinary::new("b");
+    // the results are forced whichever way the objective pushes them
+    for objective in vec![LpObjective::Maximize, LpObjective::Minimize] {
+        for &(value_a, value_b) in &[(0, 0), (0, 1), (1, 0), (1, 1)] {
+            let mut problem = LpProblem::new("Logic", objective.clone());
+            let ref and = lp_and(&mut problem, "and", &[a.clone(), b.clone()]);
+            let ref or = lp_or(&mut problem, "or", &[a.clone(), b.clone()]);
+            let ref xor = lp_xor(&mut problem, "xor", a, b);
+            let ref not = lp_not(&mut problem, "not", a);
+            problem += and + or + xor + not;
+            problem += a.equal(value_a);
+            problem += b.equal(value_b);
+
+            let actual = MiniLpSolver::new().run(&problem).expect("could not solve").results;
+            assert_eq!(actual["and"], (value_a & value_b) as f64);
+            assert_eq!(actual["or"], (value_a | value_b) as f64);
+            assert_eq!(actual["xor"], (value_a ^ value_b) as f64);
+            assert_eq!(actual["not"], (1 - value_a) as f64);
+        }
+    }
+}
+
+#[test]
+fn test_node_limit() {
+    use dsl::operations::LpOperations;
+    use dsl::BoundableLp;
+    let ref a = LpInteger::new("a").lower_bound(0.);
+    let ref b = LpInteger::new("b").lower_bound(0.);
+    let mut problem = LpProblem::new("Knapsack", LpObjective::Maximize);
+    problem += 5 * a + 4 * b;
+    problem += (6 * a + 4 * b).le(25);
+    problem += (a + 2 * b).le(6);
+
+    let solution = MiniLpSolver::new().with_max_nodes(1).run(&problem).unwrap();
+    assert_eq!((solution.status, solution.results.len()), (Status::NotSolved, 0));
+    // an integer solution is found before the search completes
+    let solution = MiniLpSolver::new().with_max_nodes(4).run(&problem).unwrap();
+    assert_eq!(solution.status, Status::SubOptimal);
+    let (a, b) = (solution.results["a"], solution.results["b"]);
+    assert!(6. * a + 4. * b <= 25. && a + 2. * b <= 6.);
+    let solution = MiniLpSolver::new().with_max_seconds(60).run(&problem).unwrap();
+    assert_eq!(solution.status, Status::Optimal);
+    assert_eq!((solution.results["a"], solution.results["b"]), (4., 0.));
+}
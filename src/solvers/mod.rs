@@ -23,9 +23,12 @@
 //! The respective information is provided in the project's README in the section on
 //! [installing external solvers](https://github.com/jcavat/rust-lp-modeler#installing-external-solvers).
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use dsl::{Problem, LpContinuous, LpBinary, LpInteger, LpProblem, LpExprNode, LpExprOp, LpExprArenaIndex};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use dsl::{Constraint, Problem, LpObjective, LpOperations, LpExpression, LpContinuous, LpBinary, LpInteger, LpSemiContinuous, LpSemiInteger, LpProblem, LpConstraint, LpExprNode, LpExprOp, LpExprArenaIndex, LpVarDict, LpVariables, IndexKey};
 
 pub mod cbc;
 pub use self::cbc::*;
@@ -46,11 +49,24 @@ pub mod native_cbc;
 #[cfg(feature = "native_coin_cbc")]
 pub use self::native_cbc::*;
 
+pub mod pareto;
+pub use self::pareto::*;
+
+pub mod feasibility;
+pub use self::feasibility::*;
+
+pub mod owned;
+pub use self::owned::*;
+
+pub mod pool;
+pub use self::pool::*;
+
 use std::fs::File;
 use std::fs;
 use util::is_zero;
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Status {
     Optimal,
     SubOptimal,
@@ -62,50 +78,92 @@ pub enum Status {
 #[derive(Debug, Clone)]
 pub struct Solution<'a> {
     pub status: Status,
-    pub results: HashMap<String, f32>,
-    pub related_problem: Option<&'a LpProblem>
+    pub results: HashMap<String, f64>,
+    pub related_problem: Option<&'a LpProblem>,
+    /// Dual values (shadow prices) by constraint identifier, for the solvers reporting them
+    pub duals: HashMap<String, f64>,
+    /// Reduced costs by variable name, for the solvers reporting them
+    pub reduced_costs: HashMap<String, f64>,
 }
 impl Solution<'_> {
-    pub fn new<'a>(status: Status, results: HashMap<String, f32>) -> Solution<'a> {
+    pub fn new<'a>(status: Status, results: HashMap<String, f64>) -> Solution<'a> {
         Solution {
             status,
             results,
-            related_problem: None
+            related_problem: None,
+            duals: HashMap::new(),
+            reduced_costs: HashMap::new(),
         }
     }
-    pub fn with_problem(status: Status, results: HashMap<String, f32>, problem: &LpProblem) -> Solution {
+    pub fn with_problem(status: Status, results: HashMap<String, f64>, problem: &LpProblem) -> Solution {
         Solution {
             status,
             results,
-            related_problem: Some(problem)
+            related_problem: Some(problem),
+            duals: HashMap::new(),
+            reduced_costs: HashMap::new(),
         }
     }
-    fn check_possible_solution(&self) {
-        match &self.status {
-            Status::Unbounded | Status::NotSolved | Status::Infeasible => panic!("Solution must be optimal or suboptimal"),
-            _ => ()
+    pub fn with_duals(self, duals: HashMap<String, f64>, reduced_costs: HashMap<String, f64>) -> Self {
+        Solution {
+            duals,
+            reduced_costs,
+            ..self
         }
     }
-    pub fn get_raw_value(&self, name: &str) -> f32 {
-        self.check_possible_solution();
-        *self.results.get(name).expect("No value found for this variable. Check if the variable has been used in the related problem.")
+    /// Same solution for `problem`, solved through a reformulation: the results, duals and
+    /// reduced costs of the auxiliary variables and constraints are dropped
+    pub(crate) fn for_problem(self, problem: &LpProblem) -> Solution<'_> {
+        let ids: HashSet<String> = (0..problem.constraints.len()).filter_map(|index| problem.constraint_id(index)).collect();
+        let mut duals = self.duals;
+        duals.retain(|id, _| ids.contains(id));
+        let variables = problem.variables();
+        let mut results = self.results;
+        results.retain(|name, _| variables.contains(name));
+        let mut reduced_costs = self.reduced_costs;
+        reduced_costs.retain(|name, _| variables.contains(name));
+        Solution::with_problem(self.status, results, problem).with_duals(duals, reduced_costs)
+    }
+    pub fn get_raw_value(&self, name: &str) -> f64 {
+        raw_value(&self.status, &self.results, name)
     }
     pub fn get_bool(&self, var: &LpBinary) -> bool {
-        self.check_possible_solution();
-        self.results.get(&var.name).and_then(|&f| if is_zero(1.0-f) { Some(true) } else if is_zero(f) { Some(false) } else { None } ).expect("Result value cannot be interpreted as boolean")
+        bool_value(&self.status, &self.results, &var.name)
     }
-    pub fn get_float(&self, var: &LpContinuous) -> f32 {
-        self.check_possible_solution();
-        *self.results.get(&var.name).expect("No value found for this variable. Check if the variable has been used in the related problem.")
+    pub fn get_float(&self, var: &LpContinuous) -> f64 {
+        raw_value(&self.status, &self.results, &var.name)
     }
     pub fn get_int(&self, var: &LpInteger) -> i32 {
-        self.check_possible_solution();
-        let &f = self.results.get(&var.name).expect("No value found for this variable. Check if the variable has been used in the related problem.");
-        let i = f as i32;
-        assert!( is_zero( f-(i as f32)), format!("Value {} cannot be interpreted as integer.", f) );
-        i
+        int_value(&self.status, &self.results, &var.name)
+    }
+    /// Copy of the solution which does not borrow the related problem
+    pub fn to_owned_solution(&self) -> OwnedSolution {
+        OwnedSolution::from(self)
+    }
+    /// Value of the variable stored at `key` in `dict`
+    pub fn get_indexed<K: IndexKey, V>(&self, dict: &LpVarDict<K, V>, key: &K) -> f64 {
+        self.get_raw_value(&dict.var_name(key))
+    }
+    /// Values of all the variables of `dict`, by key
+    pub fn get_dict<K: IndexKey, V>(&self, dict: &LpVarDict<K, V>) -> HashMap<K, f64> {
+        dict.keys().iter()
+            .map(|k| (k.clone(), self.get_indexed(dict, k)))
+            .collect()
+    }
+    /// Dual value (shadow price) of the constraint identified by `id` (see
+    /// `LpProblem::constraint_id`), if reported by the solver
+    pub fn dual(&self, id: &str) -> Option<f64> {
+        self.duals.get(id).cloned()
+    }
+    /// Reduced cost of a variable, if reported by the solver
+    pub fn reduced_cost(&self, name: &str) -> Option<f64> {
+        self.reduced_costs.get(name).cloned()
     }
-    pub fn eval(&self) -> Option<f32> {
+    /// Constraint of the related problem identified by `id` (see `LpProblem::constraint_id`)
+    pub fn constraint(&self, id: &str) -> Option<&LpConstraint> {
+        self.related_problem.and_then(|problem| problem.constraint(id))
+    }
+    pub fn eval(&self) -> Option<f64> {
         self.related_problem
             .and_then( |problem| {
                 match &problem.obj_expr_arena {
@@ -114,7 +172,46 @@ impl Solution<'_> {
                 }
             })
     }
-    fn eval_with(&self, index: &LpExprArenaIndex, values: &HashMap<String, f32>) -> f32 {
+    /// Value of `expr` for the values of the solution (0 for a variable without value)
+    pub fn eval_expr(&self, expr: &LpExpression) -> Result<f64, String> {
+        evaluate(expr, &self.results)
+    }
+    /// Activity of the constraint of the related problem identified by `id`
+    /// (see `LpProblem::constraint_id`)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use lp_modeler::dsl::*;
+    /// use lp_modeler::solvers::{Solution, Status};
+    ///
+    /// let ref a = LpContinuous::new("a");
+    /// let ref b = LpContinuous::new("b");
+    ///
+    /// let mut problem = LpProblem::new("Capacity", LpObjective::Maximize);
+    /// problem += a + b;
+    /// problem += (a + 2 * b).le(10).with_name("machine");
+    /// problem += a.le(6).with_name("labor");
+    ///
+    /// let results: HashMap<String, f64> = vec![("a".to_string(), 6.0), ("b".to_string(), 1.0)].into_iter().collect();
+    /// let solution = Solution::with_problem(Status::Optimal, results, &problem);
+    /// let machine = solution.constraint_activity("machine").unwrap();
+    /// assert_eq!((machine.activity, machine.upper, machine.slack), (8.0, Some(10.0), 2.0));
+    /// assert!(solution.constraint_activity("labor").unwrap().is_binding());
+    /// ```
+    pub fn constraint_activity(&self, id: &str) -> Result<ConstraintActivity, String> {
+        let constraint = self.constraint(id).ok_or(format!("Unknown constraint {}", id))?;
+        ConstraintActivity::of(constraint, id, &self.results)
+    }
+    /// Activities of all the constraints of the related problem, in order
+    pub fn constraint_activities(&self) -> Result<Vec<ConstraintActivity>, String> {
+        let problem = self.related_problem.ok_or("The solution has no related problem")?;
+        problem.constraints.iter().enumerate()
+            .map(|(index, constraint)| ConstraintActivity::of(constraint, &problem.constraint_id(index).unwrap(), &self.results))
+            .collect()
+    }
+    fn eval_with(&self, index: &LpExprArenaIndex, values: &HashMap<String, f64>) -> f64 {
         match self.related_problem.unwrap().obj_expr_arena.as_ref().unwrap().expr_ref_at(*index) {
             LpExprNode::LpCompExpr(operation, left, right) => {
                 match operation {
@@ -125,18 +222,130 @@ impl Solution<'_> {
             },
             LpExprNode::ConsBin(LpBinary { name })
             | LpExprNode::ConsCont(LpContinuous { name, .. })
-            | LpExprNode::ConsInt(LpInteger { name, .. }) => *values.get(name).unwrap_or(&0f32),
+            | LpExprNode::ConsSemiCont(LpSemiContinuous { name, .. })
+            | LpExprNode::ConsSemiInt(LpSemiInteger { name, .. })
+            | LpExprNode::ConsInt(LpInteger { name, .. }) => *values.get(name).unwrap_or(&0f64),
             LpExprNode::LitVal(n) => *n,
             LpExprNode::EmptyExpr => 0.0
         }
     }
 }
 
+/// Value of a constraint in a solution, once its variables are moved to the left-hand side
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstraintActivity {
+    /// Identifier of the constraint (see `LpProblem::constraint_id`)
+    pub id: String,
+    /// Value of the left-hand side
+    pub activity: f64,
+    pub lower: Option<f64>,
+    pub upper: Option<f64>,
+    /// Distance from the activity to the nearest bound, negative if the constraint is violated
+    pub slack: f64,
+}
+
+impl ConstraintActivity {
+    pub(crate) fn of(constraint: &LpConstraint, id: &str, values: &HashMap<String, f64>) -> Result<ConstraintActivity, String> {
+        let LpConstraint(lhs, op, rhs, _) = constraint.generalize();
+        let activity = evaluate(&lhs, values)?;
+        let rhs = evaluate(&rhs, values)?;
+        let (lower, upper) = match op {
+            Constraint::LessOrEqual => (None, Some(rhs)),
+            Constraint::GreaterOrEqual => (Some(rhs), None),
+            Constraint::Equal => (Some(rhs), Some(rhs)),
+            Constraint::Range(width) => (Some(rhs - width), Some(rhs)),
+        };
+        let slack = lower.map(|lower| activity - lower).into_iter()
+            .chain(upper.map(|upper| upper - activity))
+            .fold(f64::INFINITY, f64::min);
+        Ok(ConstraintActivity { id: id.to_string(), activity, lower, upper, slack })
+    }
+
+    /// Whether the activity is at one of the bounds
+    pub fn is_binding(&self) -> bool {
+        is_zero(self.slack)
+    }
+}
+
+fn raw_value(status: &Status, results: &HashMap<String, f64>, name: &str) -> f64 {
+    match status {
+        Status::Unbounded | Status::NotSolved | Status::Infeasible => panic!("Solution must be optimal or suboptimal"),
+        _ => ()
+    }
+    *results.get(name).expect("No value found for this variable. Check if the variable has been used in the related problem.")
+}
+
+fn bool_value(status: &Status, results: &HashMap<String, f64>, name: &str) -> bool {
+    let f = raw_value(status, results, name);
+    if is_zero(1.0 - f) {
+        true
+    } else if is_zero(f) {
+        false
+    } else {
+        panic!("Result value cannot be interpreted as boolean")
+    }
+}
+
+fn int_value(status: &Status, results: &HashMap<String, f64>, name: &str) -> i32 {
+    let f = raw_value(status, results, name);
+    let i = f as i32;
+    assert!( is_zero( f-(i as f64)), "Value {} cannot be interpreted as integer.", f );
+    i
+}
+
+/// Value of `expr` for the variable values `values` (0 for a missing variable)
+fn evaluate(expr: &LpExpression, values: &HashMap<String, f64>) -> Result<f64, String> {
+    let terms = expr.quadratic_terms()?;
+    let value_of = |var: &LpExprNode| *values.get(var.var_name().unwrap()).unwrap_or(&0.0);
+    let linear: f64 = terms.linear.iter().map(|(var, coefficient)| coefficient * value_of(var)).sum();
+    let quadratic: f64 = terms.quadratic.iter().map(|(a, b, coefficient)| coefficient * value_of(a) * value_of(b)).sum();
+    Ok(terms.constant + linear + quadratic)
+}
+
+/// Emulate several objectives for solvers without native support: the levels of priority
+/// are optimized one after the other, each one keeping the values reached by the previous
+/// ones (within their tolerances) with a constraint `level_{priority}`.
+pub(crate) fn solve_lexicographic<'a, S>(solver: &S, problem: &'a LpProblem) -> Result<Solution<'a>, String>
+where
+    S: SolverTrait<P = LpProblem>,
+{
+    let mut current = problem.clone();
+    current.objectives.clear();
+    let mut status = Status::NotSolved;
+    let mut results = HashMap::new();
+    for level in problem.objective_levels() {
+        current.obj_expr_arena = None;
+        current += level.expr.clone();
+        let solution = solver.run(&current)?;
+        status = solution.status;
+        results = solution.results;
+        if status != Status::Optimal && status != Status::SubOptimal {
+            break;
+        }
+        let value = evaluate(&level.expr, &results)?;
+        let tolerance = level.abs_tol.max(level.rel_tol * value.abs());
+        let level_reached = match problem.objective_type {
+            LpObjective::Minimize => level.expr.le(value + tolerance),
+            LpObjective::Maximize => level.expr.ge(value - tolerance),
+        };
+        current += level_reached.with_name(&format!("level_{}", level.priority));
+    }
+    Ok(Solution::with_problem(status, results, problem))
+}
+
 pub trait SolverTrait {
     type P: Problem;
     fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, String>;
 }
 
+/// Solvers returning several good solutions of a problem, best first. By default, the
+/// solutions are enumerated with no-good cuts (see `SolutionPool::run`).
+pub trait SolverWithSolutionPool: SolverTrait<P = LpProblem> + Sized {
+    fn run_pool<'a>(&self, problem: &'a LpProblem, pool: &SolutionPool) -> Result<Vec<Solution<'a>>, String> {
+        pool.run(self, problem)
+    }
+}
+
 pub trait SolverWithSolutionParsing {
     fn read_solution<'a>(&self, temp_solution_file: &String, problem: Option<&'a LpProblem>) -> Result<Solution<'a>, String> {
         match File::open( temp_solution_file ) {
@@ -160,3 +369,29 @@ pub trait WithNbThreads<T> {
     fn nb_threads(&self) -> Option<u32>;
     fn with_nb_threads(&self, threads: u32) -> T;
 }
+
+/// Solvers which can check the problem with `LpProblem::validate` before solving it.
+/// When enabled, `run` fails with the validation errors instead of calling the solver.
+pub trait WithValidation<T> {
+    fn validation(&self) -> bool;
+    fn with_validation(&self, validate: bool) -> T;
+}
+
+/// Solvers which can start the search of an integer solution from given values, e.g. the
+/// solution of a previous run. The values may cover only some of the variables; those which
+/// are not variables of the problem are ignored.
+pub trait WithInitialSolution<T> {
+    fn initial_solution(&self) -> Option<&HashMap<String, f64>>;
+    fn with_initial_solution(&self, values: HashMap<String, f64>) -> T;
+    /// Start from the values of `solution`
+    fn with_initial_solution_from(&self, solution: &Solution) -> T {
+        self.with_initial_solution(solution.results.clone())
+    }
+}
+
+/// Initial values of the variables of a problem, in the order of its registry
+pub(crate) fn initial_values(values: &HashMap<String, f64>, variables: &LpVariables) -> Vec<(String, f64)> {
+    variables.iter()
+        .filter_map(|var| values.get(var.name()).map(|value| (var.name().to_string(), *value)))
+        .collect()
+}
@@ -3,15 +3,21 @@ use coin_cbc;
 
 use dsl::LpExprNode::*;
 use dsl::*;
-use solvers::{Solution, SolverTrait, Status, WithMaxSeconds, WithNbThreads};
+use solvers::{Solution, SolverTrait, SolverWithSolutionPool, Status, WithInitialSolution, WithMaxSeconds, WithNbThreads, WithValidation, initial_values, solve_lexicographic};
 use std::collections::HashMap;
 
 /// Solver that calls cbc through [rust bindings](https://github.com/KardinalAI/coin_cbc)
+///
+/// The bindings (coin_cbc 0.1.9) have no API to name rows, so the constraint names and
+/// identifiers (see `LpProblem::constraint_id`) are not passed to cbc: the rows are only added
+/// in the order of `LpProblem::constraints`.
 #[derive(Debug, Clone, Default)]
 pub struct NativeCbcSolver {
     name: String,
     threads: Option<u32>,
     seconds: Option<u32>,
+    validate: bool,
+    initial_solution: Option<HashMap<String, f64>>,
 }
 
 impl NativeCbcSolver {
@@ -20,6 +26,8 @@ impl NativeCbcSolver {
             name: "CbcNative".to_string(),
             threads: None,
             seconds: None,
+            validate: false,
+            initial_solution: None,
         }
     }
 }
@@ -48,150 +56,161 @@ impl WithNbThreads<NativeCbcSolver> for NativeCbcSolver {
     }
 }
 
-impl LpExpression {
-    /// Recursively unwrap an arena of expressions.
-    /// Assumes that self.simplify() was called on the arena before starting recursion, here. That way,
-    /// `LitVal()`s of nested Multiplication expressions are already accumulated towards the left side
-    /// and `LitVal()`s of all Addition / Subtraction expressions are already accumulated towards the
-    /// right end of the tree, with all Addition / SUbtraction expressions on the most global level
-    /// possible.
-    fn var_lit(&self, expr_index: LpExprArenaIndex, lst: &mut Vec<(String, f32)>, mul: f32) {
-        match self.expr_ref_at(expr_index) {
-            &ConsBin(LpBinary { ref name, .. })
-            | &ConsInt(LpInteger { ref name, .. })
-            | &ConsCont(LpContinuous { ref name, .. }) => {
-                lst.push((name.clone(), mul));
-            }
-
-            LpCompExpr(LpExprOp::Multiplication, val, ref e) => {
-                match self.expr_ref_at(*e) {
-                    &ConsBin(LpBinary { ref name, .. })
-                    | &ConsInt(LpInteger { ref name, .. })
-                    | &ConsCont(LpContinuous { ref name, .. }) => {
-                        if let &LitVal(lit) = self.expr_ref_at(*val) {
-                            lst.push((name.clone(), mul * lit))
-                        } else {
-                            panic!("This Multiplication expression has a non-`LitVal()` left-hand side.\n\
-                                    Did you call `simplify()` before `var_lit()`? If not:\n\
-                                    This could point to a bug in LpExpression::simplify().");
-                        }
-                    },
-                    _ => {
-                        panic!("This Multiplication expression has a non-`Cons*()` right-hand side.\n\
-                                Did you call `simplify()` before `var_lit()`? If not:\n\
-                                This could point to a bug in LpExpression::simplify().");
-                    },
-                }
-            },
-            &LpCompExpr(LpExprOp::Addition, ref e1, ref e2) => {
-                self.var_lit(*e1, lst, 1.0);
-                self.var_lit(*e2, lst, 1.0);
-            }
-            &LpCompExpr(LpExprOp::Subtraction, ref e1, ref e2) => {
-                self.var_lit(*e1, lst, 1.0);
-                self.var_lit(*e2, lst, -1.0);
-            }
-            _ => (),
-        }
-    }
+/// Coefficients of the variables of a linear expression, read from its canonical terms
+fn var_lit(expr: &LpExpression) -> Result<Vec<(String, f64)>, String> {
+    let (terms, _) = expr.linear_terms()?;
+    Ok(terms.into_iter()
+        .map(|(var, coefficient)| (var.var_name().unwrap().to_string(), coefficient))
+        .collect())
 }
 
 fn always_literal(expr_arena: &LpExpression) -> f64 {
     match expr_arena.get_root_expr_ref() {
-        &LitVal(num) => num as f64,
+        &LitVal(num) => num,
         _ => panic!("wrong generalization"),
     }
 }
 
-fn add_variable(m: &mut coin_cbc::Model, expr: &LpExprNode) -> coin_cbc::Col {
-    match expr {
-        ConsInt(LpInteger {
-            name: _,
-            lower_bound,
-            upper_bound,
-        }) => {
-            let col = m.add_integer();
-            if let Some(lb) = lower_bound {
-                m.set_col_lower(col, *lb as f64)
-            }
-            if let Some(ub) = upper_bound {
-                m.set_col_upper(col, *ub as f64)
-            }
-            col
+fn add_variable(m: &mut coin_cbc::Model, var: &LpVariable) -> coin_cbc::Col {
+    let col = match var {
+        LpVariable::Integer(_) => m.add_integer(),
+        LpVariable::Continuous(_) => m.add_col(),
+        LpVariable::Binary(_) => return m.add_binary(),
+        _ => panic!("Unexpected semi-continuous variable in LpProblem.variables()!"),
+    };
+    let (lower_bound, upper_bound) = var.bounds();
+    if let Some(lb) = lower_bound {
+        m.set_col_lower(col, lb)
+    }
+    if let Some(ub) = upper_bound {
+        m.set_col_upper(col, ub)
+    }
+    col
+}
+
+impl WithValidation<NativeCbcSolver> for NativeCbcSolver {
+    fn validation(&self) -> bool {
+        self.validate
+    }
+    fn with_validation(&self, validate: bool) -> NativeCbcSolver {
+        NativeCbcSolver {
+            validate,
+            ..self.clone()
         }
-        ConsCont(LpContinuous {
-            name: _,
-            lower_bound,
-            upper_bound,
-        }) => {
-            let col = m.add_col();
-            if let Some(lb) = lower_bound {
-                m.set_col_lower(col, *lb as f64)
-            }
-            if let Some(ub) = upper_bound {
-                m.set_col_upper(col, *ub as f64)
-            }
-            col
+    }
+}
+
+impl WithInitialSolution<NativeCbcSolver> for NativeCbcSolver {
+    fn initial_solution(&self) -> Option<&HashMap<String, f64>> {
+        self.initial_solution.as_ref()
+    }
+    fn with_initial_solution(&self, values: HashMap<String, f64>) -> NativeCbcSolver {
+        NativeCbcSolver {
+            initial_solution: Some(values),
+            ..self.clone()
         }
-        ConsBin(_) => m.add_binary(),
-        _ => panic!("Unexpected LpExpression on LpProblem.variables()!"),
     }
 }
 
+impl SolverWithSolutionPool for NativeCbcSolver {}
+
 impl SolverTrait for NativeCbcSolver {
     type P = LpProblem;
 
     fn run<'a>(&self, problem: &'a Self::P) -> Result<Solution<'a>, String> {
+        if self.validate {
+            problem.validate().check()?;
+        }
+        if problem.has_multi_objectives() {
+            return solve_lexicographic(self, problem);
+        }
+        if problem.has_quadratic_objective() || problem.has_quadratic_constraints() {
+            return Err(format!("Quadratic objectives and constraints are not supported by the {} solver", self.name));
+        }
+        if !problem.indicators.is_empty() {
+            let reformulated = problem.reformulate_indicators()?;
+            return Ok(self.run(&reformulated)?.for_problem(problem));
+        }
+        let variables = problem.variables();
+        if variables.iter().any(|var| var.is_semi_continuous()) {
+            let reformulated = problem.reformulate_semi_continuous()?;
+            return Ok(self.run(&reformulated)?.for_problem(problem));
+        }
         let mut m = coin_cbc::Model::default();
         // columns (variables)
         let mut cols: HashMap<String, coin_cbc::Col> = HashMap::new();
-        for (name, (constraint_index, lp_expr_arena_index)) in problem.variables() {
-            cols.insert(name, add_variable(&mut m, problem.constraints.get(constraint_index).unwrap().0.expr_ref_at(lp_expr_arena_index) ) );
+        for var in &variables {
+            cols.insert(var.name().to_string(), add_variable(&mut m, var));
         }
         // rows (constraints)
         for cons in problem.constraints.clone() {
             let row = m.add_row();
-            let mut general = cons.generalize();
+            let general = cons.generalize();
             match general.1 {
                 Constraint::GreaterOrEqual => m.set_row_lower(row, always_literal(&general.2)),
                 Constraint::LessOrEqual => m.set_row_upper(row, always_literal(&general.2)),
                 Constraint::Equal => m.set_row_equal(row, always_literal(&general.2)),
+                Constraint::Range(width) => {
+                    let upper = always_literal(&general.2);
+                    m.set_row_lower(row, upper - width);
+                    m.set_row_upper(row, upper);
+                }
             }
-            let mut lst: Vec<_> = Vec::new();
-            general.0.simplify();
-            let root_index = general.0.get_root_index();
-            general.0.var_lit(root_index, &mut lst, 1.0);
-            lst.iter()
-                .for_each(|(n, lit)| m.set_weight(row, cols[n], *lit as f64));
+            var_lit(&general.0)?.iter()
+                .for_each(|(n, lit)| m.set_weight(row, cols[n], *lit));
         };
+        // special ordered sets
+        for sos in &problem.sos {
+            let weighted_cols: Vec<(coin_cbc::Col, f64)> = sos.weighted_vars.iter()
+                .map(|(name, weight)| {
+                    cols.get(name)
+                        .map(|col| (*col, *weight))
+                        .ok_or(format!("Variable {} of the set {} is not used in the problem", name, sos.name))
+                })
+                .collect::<Result<_, String>>()?;
+            match sos.sos_type {
+                SosType::Sos1 => m.add_sos1(weighted_cols),
+                SosType::Sos2 => m.add_sos2(weighted_cols),
+            }
+        }
         // objective
         if let Some(objective) = &problem.obj_expr_arena {
-            let mut lst: Vec<_> = Vec::new();
-            let mut cloned_objective = objective.clone();
-            cloned_objective.simplify();
-            let root_index = cloned_objective.get_root_index();
-            cloned_objective.var_lit(root_index, &mut lst, 1.0);
-            lst.iter()
-                .for_each(|(n, lit)| m.set_obj_coeff(cols[n], *lit as f64))
+            var_lit(objective)?.iter()
+                .for_each(|(n, lit)| m.set_obj_coeff(cols[n], *lit))
         }
         m.set_obj_sense(match problem.objective_type {
             LpObjective::Maximize => coin_cbc::Sense::Maximize,
             LpObjective::Minimize => coin_cbc::Sense::Minimize,
         });
+        // initial solution, once all the columns are added
+        if let Some(values) = &self.initial_solution {
+            for (name, value) in initial_values(values, &variables) {
+                m.set_col_initial_solution(cols[&name], value);
+            }
+        }
 
         let sol = m.solve();
 
-        Ok(Solution {
-            status: match sol.raw().status() {
-                coin_cbc::raw::Status::Finished => Status::Optimal,
-                coin_cbc::raw::Status::Abandoned => Status::Infeasible,
-                _ => Status::NotSolved,
-            },
-            results: cols
-                .iter()
-                .map(|(name, col)| (name.to_owned(), sol.col(*col) as f32))
-                .collect(),
-            related_problem: Some(problem),
-        })
+        let status = match sol.raw().status() {
+            coin_cbc::raw::Status::Finished => Status::Optimal,
+            coin_cbc::raw::Status::Abandoned => Status::Infeasible,
+            _ => Status::NotSolved,
+        };
+        let results = cols
+            .iter()
+            .map(|(name, col)| (name.to_owned(), sol.col(*col)))
+            .collect();
+        let solution = Solution::with_problem(status, results, problem);
+        // the C API of cbc gives no access to the duals of the rows
+        #[cfg(feature = "native_coin_cbc_310")]
+        let solution = {
+            // columns are numbered in the order of the variables
+            let reduced_costs = variables.names()
+                .zip(sol.raw().reduced_cost())
+                .map(|(name, reduced_cost)| (name.to_string(), *reduced_cost))
+                .collect();
+            solution.with_duals(HashMap::new(), reduced_costs)
+        };
+        Ok(solution)
     }
 }
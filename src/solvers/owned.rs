@@ -0,0 +1,153 @@
+//! Solutions which do not borrow their problem, to be stored, sent to other threads or
+//! serialized with the `serde` feature.
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use dsl::{LpBinary, LpContinuous, LpInteger, LpVariable};
+use solvers::{bool_value, int_value, raw_value, Solution, Status};
+
+/// Type of a variable of the problem of an `OwnedSolution`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum VariableType {
+    Binary,
+    Integer,
+    Continuous,
+    SemiContinuous,
+    SemiInteger,
+}
+
+impl<'a> From<&'a LpVariable> for VariableType {
+    fn from(var: &'a LpVariable) -> Self {
+        match var {
+            LpVariable::Binary(_) => VariableType::Binary,
+            LpVariable::Integer(_) => VariableType::Integer,
+            LpVariable::Continuous(_) => VariableType::Continuous,
+            LpVariable::SemiContinuous(_) => VariableType::SemiContinuous,
+            LpVariable::SemiInteger(_) => VariableType::SemiInteger,
+        }
+    }
+}
+
+/// Objective of a problem, by variable names
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ObjectiveTerms {
+    pub constant: f64,
+    pub linear: Vec<(String, f64)>,
+    /// Products of two variables with their coefficient
+    pub quadratic: Vec<(String, String, f64)>,
+}
+
+impl ObjectiveTerms {
+    /// Value of the objective (0 for a variable without value)
+    pub fn eval(&self, values: &HashMap<String, f64>) -> f64 {
+        let value_of = |name: &String| *values.get(name).unwrap_or(&0.0);
+        let linear: f64 = self.linear.iter().map(|(name, coefficient)| coefficient * value_of(name)).sum();
+        let quadratic: f64 = self.quadratic.iter().map(|(a, b, coefficient)| coefficient * value_of(a) * value_of(b)).sum();
+        self.constant + linear + quadratic
+    }
+}
+
+/// Solution keeping the objective and the variable types of its problem instead of a
+/// reference to it
+///
+/// # Examples
+///
+/// ```
+/// use std::thread;
+/// use lp_modeler::dsl::*;
+/// use lp_modeler::solvers::{OwnedSolution, Solution, Status, VariableType};
+///
+/// let ref a = LpInteger::new("a");
+/// let ref b = LpBinary::new("b");
+///
+/// let mut problem = LpProblem::new("Owned", LpObjective::Maximize);
+/// problem += 3 * a + 2 * b;
+/// problem += (a + b).le(4);
+///
+/// let results = vec![("a".to_string(), 3.0), ("b".to_string(), 1.0)].into_iter().collect();
+/// let owned: OwnedSolution = Solution::with_problem(Status::Optimal, results, &problem).to_owned_solution();
+///
+/// let handle = thread::spawn(move || (owned.eval(), owned.get_int(&LpInteger::new("a")), owned.get_bool(&LpBinary::new("b"))));
+/// assert_eq!(handle.join().unwrap(), (Some(11.0), 3, true));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OwnedSolution {
+    pub status: Status,
+    pub results: HashMap<String, f64>,
+    /// Dual values (shadow prices) by constraint identifier, for the solvers reporting them
+    pub duals: HashMap<String, f64>,
+    /// Reduced costs by variable name, for the solvers reporting them
+    pub reduced_costs: HashMap<String, f64>,
+    /// Objective of the problem, if it has one of degree at most two
+    pub objective: Option<ObjectiveTerms>,
+    /// Types of the variables of the problem
+    pub variable_types: HashMap<String, VariableType>,
+}
+
+impl<'a, 'b> From<&'b Solution<'a>> for OwnedSolution {
+    fn from(solution: &'b Solution<'a>) -> Self {
+        let problem = solution.related_problem;
+        let objective = problem
+            .and_then(|problem| problem.obj_expr_arena.as_ref())
+            .and_then(|objective| objective.quadratic_terms().ok())
+            .map(|terms| ObjectiveTerms {
+                constant: terms.constant,
+                linear: terms.linear.iter()
+                    .map(|(var, coefficient)| (var.var_name().unwrap().to_string(), *coefficient))
+                    .collect(),
+                quadratic: terms.quadratic.iter()
+                    .map(|(a, b, coefficient)| (a.var_name().unwrap().to_string(), b.var_name().unwrap().to_string(), *coefficient))
+                    .collect(),
+            });
+        let variable_types = problem
+            .map(|problem| problem.variables().iter().map(|var| (var.name().to_string(), VariableType::from(var))).collect())
+            .unwrap_or_default();
+        OwnedSolution {
+            status: solution.status.clone(),
+            results: solution.results.clone(),
+            duals: solution.duals.clone(),
+            reduced_costs: solution.reduced_costs.clone(),
+            objective,
+            variable_types,
+        }
+    }
+}
+
+impl<'a> From<Solution<'a>> for OwnedSolution {
+    fn from(solution: Solution<'a>) -> Self {
+        OwnedSolution::from(&solution)
+    }
+}
+
+impl OwnedSolution {
+    pub fn get_raw_value(&self, name: &str) -> f64 {
+        raw_value(&self.status, &self.results, name)
+    }
+    pub fn get_bool(&self, var: &LpBinary) -> bool {
+        bool_value(&self.status, &self.results, &var.name)
+    }
+    pub fn get_float(&self, var: &LpContinuous) -> f64 {
+        raw_value(&self.status, &self.results, &var.name)
+    }
+    pub fn get_int(&self, var: &LpInteger) -> i32 {
+        int_value(&self.status, &self.results, &var.name)
+    }
+    /// Value of the objective of the problem
+    pub fn eval(&self) -> Option<f64> {
+        self.objective.as_ref().map(|objective| objective.eval(&self.results))
+    }
+    pub fn variable_type(&self, name: &str) -> Option<VariableType> {
+        self.variable_types.get(name).cloned()
+    }
+    pub fn dual(&self, id: &str) -> Option<f64> {
+        self.duals.get(id).cloned()
+    }
+    pub fn reduced_cost(&self, name: &str) -> Option<f64> {
+        self.reduced_costs.get(name).cloned()
+    }
+}
@@ -0,0 +1,175 @@
+//! Pareto front enumeration with the epsilon-constraint method.
+use std::collections::HashMap;
+
+use dsl::{LpExpression, LpObjective, LpOperations, LpProblem};
+use solvers::{evaluate, SolverTrait, Solution, Status};
+use util::is_zero;
+
+/// Driver enumerating the non-dominated trade-offs between a primary objective and
+/// secondary objectives, all optimized in the direction of the problem
+/// (`LpProblem::objective_type`).
+///
+/// The range of each secondary objective is first taken from the payoff table (every
+/// objective optimized alone). The primary objective is then optimized for every
+/// combination of bounds `epsilon_{k}` stepped from the worst to the best value of the
+/// secondary objectives, and the dominated solutions are discarded.
+///
+/// # Examples
+///
+/// ```no_run
+/// use lp_modeler::dsl::*;
+/// use lp_modeler::solvers::{CbcSolver, EpsilonConstraint};
+///
+/// let ref cost = LpContinuous::new("cost");
+/// let ref emissions = LpContinuous::new("emissions");
+///
+/// let mut problem = LpProblem::new("Plan", LpObjective::Minimize);
+/// problem += (cost + emissions).ge(10);
+/// problem += cost.ge(2);
+/// problem += emissions.ge(2);
+///
+/// let front = EpsilonConstraint::new(cost)
+///     .with_objective(emissions, 4)
+///     .run(&CbcSolver::new(), &problem)
+///     .unwrap();
+/// for point in front {
+///     println!("cost {} emissions {}", point.objectives[0], point.objectives[1]);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpsilonConstraint {
+    pub primary: LpExpression,
+    /// Secondary objectives with their number of steps
+    pub secondaries: Vec<(LpExpression, usize)>,
+}
+
+/// Non-dominated solution with the values of the objectives, the primary one first
+#[derive(Debug, Clone)]
+pub struct ParetoSolution<'a> {
+    pub objectives: Vec<f64>,
+    pub solution: Solution<'a>,
+}
+
+impl EpsilonConstraint {
+    pub fn new<T: Into<LpExpression>>(primary: T) -> EpsilonConstraint {
+        EpsilonConstraint {
+            primary: primary.into(),
+            secondaries: Vec::new(),
+        }
+    }
+
+    /// Add a secondary objective whose range is split in `steps` intervals
+    pub fn with_objective<T: Into<LpExpression>>(&self, objective: T, steps: usize) -> EpsilonConstraint {
+        let mut secondaries = self.secondaries.clone();
+        secondaries.push((objective.into(), steps));
+        EpsilonConstraint { secondaries, ..self.clone() }
+    }
+
+    fn objectives(&self) -> Vec<&LpExpression> {
+        Some(&self.primary).into_iter()
+            .chain(self.secondaries.iter().map(|(objective, _)| objective))
+            .collect()
+    }
+
+    /// Non-dominated solutions sorted by the value of the primary objective. Combinations of
+    /// bounds without optimal solution are skipped; `Err` if an objective alone cannot be
+    /// optimized.
+    pub fn run<'a, S>(&self, solver: &S, problem: &'a LpProblem) -> Result<Vec<ParetoSolution<'a>>, String>
+    where
+        S: SolverTrait<P = LpProblem>,
+    {
+        let mut base = problem.clone();
+        base.objectives.clear();
+        let objectives = self.objectives();
+
+        // payoff table: values of all the objectives when optimizing each one alone
+        let mut payoff = Vec::with_capacity(objectives.len());
+        for (k, objective) in objectives.iter().enumerate() {
+            let mut current = base.clone();
+            current.obj_expr_arena = None;
+            current += (*objective).clone();
+            let solution = solver.run(&current)?;
+            if !is_solved(&solution.status) {
+                return Err(format!("Objective {} cannot be optimized alone: {:?}", k, solution.status));
+            }
+            payoff.push(evaluate_all(&objectives, &solution.results)?);
+        }
+
+        let grids: Vec<Vec<f64>> = self.secondaries.iter().enumerate()
+            .map(|(k, &(_, steps))| {
+                let best = payoff[k + 1][k + 1];
+                let worst = payoff.iter().map(|values| values[k + 1])
+                    .fold(best, |worst, value| if is_better(problem, worst, value) { value } else { worst });
+                (0..=steps)
+                    .map(|step| if steps == 0 { worst } else { worst + (best - worst) * step as f64 / steps as f64 })
+                    .collect()
+            })
+            .collect();
+
+        let mut points: Vec<ParetoSolution<'a>> = Vec::new();
+        for epsilons in combinations(&grids) {
+            let mut current = base.clone();
+            current.obj_expr_arena = None;
+            current += self.primary.clone();
+            for (k, (&epsilon, (objective, _))) in epsilons.iter().zip(&self.secondaries).enumerate() {
+                let bound = match problem.objective_type {
+                    LpObjective::Minimize => objective.le(epsilon),
+                    LpObjective::Maximize => objective.ge(epsilon),
+                };
+                current += bound.with_name(&format!("epsilon_{}", k));
+            }
+            let solution = solver.run(&current)?;
+            if !is_solved(&solution.status) {
+                continue;
+            }
+            let values = evaluate_all(&objectives, &solution.results)?;
+            if points.iter().any(|point| dominates_or_equals(problem, &point.objectives, &values)) {
+                continue;
+            }
+            points.retain(|point| !dominates_or_equals(problem, &values, &point.objectives));
+            points.push(ParetoSolution {
+                objectives: values,
+                solution: Solution::with_problem(solution.status, solution.results, problem),
+            });
+        }
+        points.sort_by(|a, b| a.objectives[0].partial_cmp(&b.objectives[0]).unwrap());
+        if problem.objective_type == LpObjective::Maximize {
+            points.reverse();
+        }
+        Ok(points)
+    }
+}
+
+fn is_solved(status: &Status) -> bool {
+    *status == Status::Optimal || *status == Status::SubOptimal
+}
+
+fn evaluate_all(objectives: &[&LpExpression], results: &HashMap<String, f64>) -> Result<Vec<f64>, String> {
+    objectives.iter().map(|objective| evaluate(objective, results)).collect()
+}
+
+/// `a` strictly better than `b` in the direction of the problem
+fn is_better(problem: &LpProblem, a: f64, b: f64) -> bool {
+    !is_zero(a - b) && match problem.objective_type {
+        LpObjective::Minimize => a < b,
+        LpObjective::Maximize => a > b,
+    }
+}
+
+/// `a` at least as good as `b` on every objective
+fn dominates_or_equals(problem: &LpProblem, a: &[f64], b: &[f64]) -> bool {
+    a.iter().zip(b).all(|(&a, &b)| !is_better(problem, b, a))
+}
+
+/// Cartesian product of the grids
+fn combinations(grids: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    grids.iter().fold(vec![Vec::new()], |combinations, grid| {
+        combinations.iter()
+            .flat_map(|combination| grid.iter().map(move |&value| {
+                let mut next = combination.clone();
+                next.push(value);
+                next
+            }))
+            .collect()
+    })
+}
@@ -0,0 +1,160 @@
+//! Enumeration of several good solutions of a problem.
+use std::cmp::Ordering;
+
+use dsl::{LpBinary, LpExpression, LpLinearExpression, LpObjective, LpOperations, LpProblem, LpVariable};
+use solvers::{evaluate, SolverTrait, Solution, Status};
+use util::is_zero;
+
+/// Options of a pool of solutions: how many solutions to return at most, and how much worse
+/// than the best one they may be.
+///
+/// `run` is the generic enumerator: the problem is solved again and again, each time with a
+/// no-good cut `no_good_{k}` excluding the assignment of the binary variables of the previous
+/// solutions, until `max_solutions` solutions are found, the problem becomes infeasible or the
+/// objective leaves the gap. Only the binary variables tell solutions apart, so a problem
+/// without binaries has a single solution in its pool.
+///
+/// With several objectives (see `LpMultiObjective`), solutions are ordered lexicographically
+/// by the values of the levels of priority, and the gap applies to each level.
+///
+/// # Examples
+///
+/// ```no_run
+/// use lp_modeler::dsl::*;
+/// use lp_modeler::solvers::{GlpkSolver, SolutionPool, SolverWithSolutionPool};
+///
+/// let ref a = LpBinary::new("a");
+/// let ref b = LpBinary::new("b");
+/// let ref c = LpBinary::new("c");
+///
+/// let mut problem = LpProblem::new("Pick", LpObjective::Maximize);
+/// problem += 5 * a + 4 * b + 3 * c;
+/// problem += (a + b + c).le(2);
+///
+/// let pool = SolutionPool::new(3).with_gap(0.2);
+/// for solution in GlpkSolver::new().run_pool(&problem, &pool).unwrap() {
+///     println!("{:?} {:?}", solution.eval(), solution.results);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolutionPool {
+    pub max_solutions: usize,
+    /// Largest relative difference between the objective of a solution and the best one
+    pub gap: Option<f64>,
+}
+
+impl SolutionPool {
+    pub fn new(max_solutions: usize) -> SolutionPool {
+        SolutionPool {
+            max_solutions,
+            gap: None,
+        }
+    }
+
+    pub fn with_gap(&self, gap: f64) -> SolutionPool {
+        SolutionPool {
+            gap: Some(gap),
+            ..self.clone()
+        }
+    }
+
+    /// Solutions found by adding no-good cuts, best first. `Err` if the solver fails.
+    pub fn run<'a, S>(&self, solver: &S, problem: &'a LpProblem) -> Result<Vec<Solution<'a>>, String>
+    where
+        S: SolverTrait<P = LpProblem>,
+    {
+        let binaries: Vec<LpBinary> = problem.variables().iter()
+            .filter_map(|var| match var {
+                LpVariable::Binary(binary) => Some(binary.clone()),
+                _ => None,
+            })
+            .collect();
+        let mut current = problem.clone();
+        let mut solutions: Vec<Solution<'a>> = Vec::new();
+        while solutions.len() < self.max_solutions {
+            let solution = solver.run(&current)?;
+            if solution.status != Status::Optimal && solution.status != Status::SubOptimal {
+                break;
+            }
+            if let Some(best) = solutions.first() {
+                if !self.within_gap(&objective_values(problem, best)?, &objective_values(problem, &solution)?) {
+                    break;
+                }
+            }
+            // sum of the binaries at 0 and of the complements of those at 1
+            let mut cut = LpLinearExpression::new();
+            for binary in &binaries {
+                if is_zero(1.0 - *solution.results.get(&binary.name).unwrap_or(&0.0)) {
+                    cut.add_term(binary, -1.0);
+                    cut.add_constant(1.0);
+                } else {
+                    cut.add_term(binary, 1.0);
+                }
+            }
+            solutions.push(solution.for_problem(problem));
+            if binaries.is_empty() {
+                break;
+            }
+            current += LpExpression::from(cut).ge(1).with_name(&format!("no_good_{}", solutions.len()));
+        }
+        self.select(problem, solutions)
+    }
+
+    /// Best solutions within the gap, best first, for the solvers returning several solutions
+    pub(crate) fn select<'a>(&self, problem: &LpProblem, solutions: Vec<Solution<'a>>) -> Result<Vec<Solution<'a>>, String> {
+        // the best solution is usually reported twice
+        let mut distinct: Vec<Solution<'a>> = Vec::new();
+        for solution in solutions {
+            if !distinct.iter().any(|other| other.results == solution.results) {
+                distinct.push(solution);
+            }
+        }
+        let mut valued = distinct.into_iter()
+            .map(|solution| objective_values(problem, &solution).map(|values| (values, solution)))
+            .collect::<Result<Vec<_>, String>>()?;
+        valued.sort_by(|(a, _), (b, _)| {
+            let order = compare_values(a, b);
+            match problem.objective_type {
+                LpObjective::Minimize => order,
+                LpObjective::Maximize => order.reverse(),
+            }
+        });
+        let best = valued.first().map(|(values, _)| values.clone()).unwrap_or_default();
+        Ok(valued.into_iter()
+            .filter(|(values, _)| self.within_gap(&best, values))
+            .take(self.max_solutions)
+            .map(|(values, mut solution)| {
+                // only the solutions as good as the best one keep their optimal status
+                if solution.status == Status::Optimal && compare_values(&values, &best) != Ordering::Equal {
+                    solution.status = Status::SubOptimal;
+                }
+                solution
+            })
+            .collect())
+    }
+
+    /// Whether the value of each level of `values` is within the gap of the best one
+    fn within_gap(&self, best: &[f64], values: &[f64]) -> bool {
+        match self.gap {
+            Some(gap) => best.iter().zip(values)
+                .all(|(best, value)| is_zero(value - best) || (value - best).abs() <= gap * best.abs()),
+            None => true,
+        }
+    }
+}
+
+/// Values of the levels of priority of the objectives of `problem`, from the highest (a single
+/// value for a single objective, none without objective)
+fn objective_values(problem: &LpProblem, solution: &Solution) -> Result<Vec<f64>, String> {
+    problem.objective_levels().iter()
+        .map(|level| evaluate(&level.expr, &solution.results))
+        .collect()
+}
+
+/// Lexicographic order of the values of the levels, equal up to the tolerance of `is_zero`
+fn compare_values(a: &[f64], b: &[f64]) -> Ordering {
+    a.iter().zip(b)
+        .map(|(a, b)| if is_zero(a - b) { Ordering::Equal } else { a.partial_cmp(b).unwrap_or(Ordering::Equal) })
+        .find(|order| *order != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
@@ -1,9 +1,10 @@
-pub fn is_zero(n: f32) -> bool {
+pub fn is_zero(n: f64) -> bool {
     n.abs() < 0.00001
 }
 
 /// This macro allows defining constraints using 'expression1 <= expression2'
-/// instead of `expression1.le(expression2)`. 
+/// instead of `expression1.le(expression2)`, and range constraints using
+/// 'lower <= expression <= upper' instead of `expression.between(lower, upper)`.
 /// 
 /// # Example:
 ///
@@ -18,15 +19,36 @@ pub fn is_zero(n: f32) -> bool {
 /// problem += 5*a + 3*b;
 /// problem += constraint!(a + b*2 <= 10);
 /// problem += constraint!(b >= a);
+/// problem += constraint!(2 <= a - b <= 4);
 /// ```
 #[macro_export]
 macro_rules! constraint {
-    ([$($left:tt)*] <= $($right:tt)*) => {
+    // A second comparison makes a range: `lower <= expr <= upper`
+    (@le [$($left:tt)*] [$($middle:tt)*] <= $($right:tt)*) => {
+        ($($middle)*).between($($left)*, $($right)*)
+    };
+    (@le [$($left:tt)*] [$($right:tt)*]) => {
         ($($left)*).le($($right)*)
     };
-    ([$($left:tt)*] >= $($right:tt)*) => {
+    (@le [$($left:tt)*] [$($middle:tt)*] $next:tt $($right:tt)*) => {
+        constraint!(@le [$($left)*] [$($middle)* $next] $($right)*)
+    };
+    // `upper >= expr >= lower`
+    (@ge [$($left:tt)*] [$($middle:tt)*] >= $($right:tt)*) => {
+        ($($middle)*).between($($right)*, $($left)*)
+    };
+    (@ge [$($left:tt)*] [$($right:tt)*]) => {
         ($($left)*).ge($($right)*)
     };
+    (@ge [$($left:tt)*] [$($middle:tt)*] $next:tt $($right:tt)*) => {
+        constraint!(@ge [$($left)*] [$($middle)* $next] $($right)*)
+    };
+    ([$($left:tt)*] <= $($right:tt)*) => {
+        constraint!(@le [$($left)*] [] $($right)*)
+    };
+    ([$($left:tt)*] >= $($right:tt)*) => {
+        constraint!(@ge [$($left)*] [] $($right)*)
+    };
     // Stop condition: all token have been processed
     ([$($left:tt)*]) => {
         $($left:tt)*
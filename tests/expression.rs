@@ -16,10 +16,10 @@ fn distributivity() {
     assert_eq!((2 * (2 + a)).to_lp_file_format(), "2 a + 4");
     assert_eq!(
         ((2 + a) * (2 + b)).to_lp_file_format(),
-        "2 a + 2 b + b a + 4"
+        "2 a + 2 b + [ a * b ] + 4"
     );
-    assert_eq!(test.to_lp_file_format(), "6 a + 3 a b");
-    assert_eq!((10 * test).to_lp_file_format(), "60 a + 30 a b");
+    assert_eq!(test.to_lp_file_format(), "6 a + [ 3 a * b ]");
+    assert_eq!((10 * test).to_lp_file_format(), "60 a + [ 30 a * b ]");
     assert_eq!(
         ((c + 10) * test2).to_lp_file_format(),
         "6 c a + 3 c a b + 60 a + 30 a b"
@@ -31,8 +31,8 @@ fn distributivity() {
 
     let test3 = x * (y - z);
     assert_eq!((3 * (3 - a)).to_lp_file_format(), "-3 a + 9");
-    assert_eq!(test3.to_lp_file_format(), "x y - x z");
-    assert_eq!((4 * test3).to_lp_file_format(), "4 x y - 4 x z");
+    assert_eq!(test3.to_lp_file_format(), "[ x * y - x * z ]");
+    assert_eq!((4 * test3).to_lp_file_format(), "[ 4 x * y - 4 x * z ]");
 }
 
 #[test]
@@ -70,28 +70,28 @@ fn literal_first_with_accumulation() {
 
     assert_eq!(
         (a + 1 + b + 2 + c + 3 + a + 4).to_lp_file_format(),
-        "a + b + c + a + 10"
+        "2 a + b + c + 10"
     );
     assert_eq!(
         (a - 1 + b - 2 - c + 3 + a - 4).to_lp_file_format(),
-        "a + b - c + a - 4"
+        "2 a + b - c - 4"
     );
     assert_eq!(
         (a + b + 1 - c - a - 3).to_lp_file_format(),
-        "a + b - c - a - 2"
+        "b - c - 2"
     );
     assert_eq!(
         (a + b + (c - 1) * 2 - a - 3).to_lp_file_format(),
-        "a + b + 2 c - a - 5"
+        "b + 2 c - 5"
     );
     assert_eq!(
         (a + b + (1 - c) * 2 - a - 3).to_lp_file_format(),
-        "a + b - 2 c - a - 1"
+        "b - 2 c - 1"
     );
     assert_eq!((2 * (a + 5)).to_lp_file_format(), "2 a + 10");
     assert_eq!(
         ((2 + b) * (a + 5)).to_lp_file_format(),
-        "2 a + a b + 5 b + 10"
+        "5 b + 2 a + [ a * b ] + 10"
     );
     assert_eq!((2 + (a + b) + 3).to_lp_file_format(), "a + b + 5");
 }
@@ -112,6 +112,17 @@ fn trivial_rules() {
     assert_eq!((0 + (a + b)).to_lp_file_format(), "a + b");
 }
 
+#[test]
+fn unsuffixed_float_literals() {
+    let ref a = LpInteger::new("a");
+    let ref b = LpContinuous::new("b");
+
+    assert_eq!((2.0 * a).le(10.).to_lp_file_format(), "2 a <= 10");
+    assert_eq!((a + 1.5).ge(0.5 * b).to_lp_file_format(), "a - 0.5 b >= -1.5");
+    assert_eq!((2. * a - 8. * b + -4. * a).equal(3.).to_lp_file_format(), "-2 a - 8 b = 3");
+    assert_eq!(a.between(1., 2.5).to_lp_file_format(), "1 <= a <= 2.5");
+}
+
 #[test]
 fn expressions_to_lp_file_format() {
     let ref a = LpInteger::new("a");
@@ -148,7 +159,7 @@ fn expressions_to_lp_file_format() {
     );
     assert_eq!(
         (3 * (a + b + 10)).le(a + b).to_lp_file_format(),
-        "3 a + 3 b - a - b <= -30"
+        "2 a + 2 b <= -30"
     );
 }
 
@@ -192,4 +203,60 @@ fn macros(){
         constraint!(2 * a + b + 20 >= c).to_lp_file_format(),
         "2 a + b - c >= -20"
     );
+    assert_eq!(
+        constraint!(2 <= a + b + 1 <= 10 + 2).to_lp_file_format(),
+        "1 <= a + b <= 11"
+    );
+    assert_eq!(
+        constraint!(10 >= a - c >= -5).to_lp_file_format(),
+        "-5 <= a - c <= 10"
+    );
+}
+
+#[test]
+fn linear_expression() {
+    let ref a = LpInteger::new("a");
+    let ref b = LpContinuous::new("b");
+
+    let mut linear = LpLinearExpression::new();
+    linear += a;
+    linear.add_term(b, 3.0);
+    linear -= b;
+    linear.add_term(2, 5.0);
+    assert_eq!(linear.len(), 2);
+    assert_eq!(linear.coefficient("b"), 2.0);
+    assert_eq!(linear.constant(), 10.0);
+    let expr: LpExpression = linear.clone().into();
+    assert_eq!(expr.to_lp_file_format(), "a + 2 b + 10");
+    assert_eq!(expr.to_linear(), Ok(linear.clone()));
+    assert_eq!((-linear.clone()).ge(a).to_lp_file_format(), "-2 a - 2 b >= 10");
+    assert_eq!((linear.clone() - linear).to_lp_file_format(), "0");
+
+    let product = (a + b) * (a + 1);
+    assert!(product.to_linear().is_err());
+
+    let ref vars: Vec<LpContinuous> = (0..100_000).map(|i| LpContinuous::new(&format!("x{}", i))).collect();
+    let sum: LpLinearExpression = vars.iter().map(|var| (var, 2.0)).collect();
+    let constraint = sum.le(10);
+    assert_eq!((constraint.0.to_linear().unwrap().len(), constraint.1), (100_000, Constraint::LessOrEqual));
+}
+
+#[test]
+fn large_sum_with_operators() {
+    let ref vars: Vec<LpContinuous> = (0..100_000).map(|i| LpContinuous::new(&format!("x{}", i))).collect();
+
+    let sum = vars.iter().fold(LpExpression::literal(1.0), |sum, var| sum + 2 * var);
+    let mut difference = LpExpression::literal(0.0);
+    for var in vars.iter().step_by(2) {
+        difference -= var;
+    }
+    let linear = (sum + difference).to_linear().unwrap();
+    assert_eq!((linear.len(), linear.coefficient("x0"), linear.coefficient("x1"), linear.constant()), (100_000, 1.0, 2.0, 1.0));
+
+    let mut problem = LpProblem::new("Large", LpObjective::Maximize);
+    problem += vars.iter().fold(LpExpression::literal(0.0), |sum, var| sum + var);
+    problem += vars.iter().fold(LpExpression::literal(0.0), |sum, var| sum + var).le(10);
+    let lp = problem.to_lp_file_format();
+    assert!(lp.contains("x0 + x1 + x2"));
+    assert!(lp.contains("x99998 + x99999 <= 10"));
 }
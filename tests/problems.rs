@@ -1,12 +1,14 @@
 extern crate lp_modeler;
 
+use std::cell::RefCell;
 use std::collections::HashMap;
 
-use lp_modeler::solvers::{CbcSolver, SolverTrait, Solution};
+use lp_modeler::solvers::{CbcSolver, EpsilonConstraint, FeasibilityCheck, LpViolation, SolverTrait, Solution, Status, WithValidation};
 #[cfg(feature = "native_coin_cbc")]
 use lp_modeler::solvers::NativeCbcSolver;
 use lp_modeler::dsl::*;
 use lp_modeler::format::lp_format::LpFileFormat;
+use lp_modeler::display_index_key;
 
 #[test]
 fn test_readme_example_1() {
@@ -80,12 +82,642 @@ fn test_full_example() {
     }
 }
 
+#[test]
+fn test_named_constraints() {
+    let ref a = LpInteger::new("a");
+    let ref b = LpInteger::new("b");
+
+    let mut problem = LpProblem::new("Named", LpObjective::Maximize);
+    problem += a + b;
+    problem += (a + b).le(10).with_name("capacity");
+    problem += a.le(b);
+
+    let output = problem.to_lp_file_format();
+    assert!(output.contains("  capacity: a + b <= 10\n"));
+    assert!(output.contains("  c2: a - b <= 0\n"));
+
+    assert_eq!(problem.constraint_index("capacity"), Some(0));
+    assert_eq!(problem.constraint_id(1), Some("c2".to_string()));
+    assert_eq!(problem.constraint("c2").and_then(|c| c.name()), None);
+    assert!(problem.constraint("missing").is_none());
+    assert!(problem.constraint("c02").is_none());
+    assert!(problem.constraint("c1").is_none());
+
+    problem += a.ge(1).with_name("c4");
+    problem += b.ge(1);
+    assert_eq!(problem.constraint_index("c4"), Some(2));
+    let indices = problem.constraint_indices();
+    assert_eq!(indices.len(), 3);
+    assert_eq!((indices["capacity"], indices["c2"], indices["c4"]), (0, 1, 2));
+}
+
+#[test]
+fn test_variable_dict() {
+    let keys: Vec<(i32, &str)> = vec![(1, "a"), (1, "b"), (2, "a")];
+    let ref x = LpVarDict::integer("x", keys).lower_bound(0.0).upper_bound(5.0);
+    assert_eq!(x.len(), 3);
+    assert_eq!(x.var_name(&(2, "a")), "x_2_a");
+    assert!(x.get(&(2, "b")).is_none());
+
+    let mut problem = LpProblem::new("Dict", LpObjective::Maximize);
+    problem += x.sum();
+    for (i, row) in x.sum_by(|&(i, _)| i) {
+        problem += row.le(i);
+    }
+    problem += x.sum_where(|&(_, j)| j == "a").ge(1);
+
+    let output = problem.to_lp_file_format();
+    assert!(output.contains("obj: x_1_a + x_1_b + x_2_a"));
+    assert!(output.contains("c1: x_1_a + x_1_b <= 1"));
+    assert!(output.contains("c2: x_2_a <= 2"));
+    assert!(output.contains("c3: x_1_a + x_2_a >= 1"));
+    assert!(output.contains("0 <= x_1_b <= 5"));
+
+    let results: HashMap<String, f64> = vec![
+        ("x_1_a".to_string(), 1.0),
+        ("x_1_b".to_string(), 0.0),
+        ("x_2_a".to_string(), 2.0),
+    ].into_iter().collect();
+    let solution = Solution::new(Status::Optimal, results);
+    assert_eq!(solution.get_indexed(x, &(2, "a")), 2.0);
+    assert_eq!(solution.get_dict(x)[&(1, "b")], 0.0);
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Shift {
+    Day,
+    Night,
+}
+
+impl std::fmt::Display for Shift {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", if *self == Shift::Day { "day" } else { "night" })
+    }
+}
+display_index_key!(Shift);
+
+#[test]
+fn test_variable_dict_user_key() {
+    let ref staff = LpVarDict::integer("staff", vec![(Shift::Day, 1), (Shift::Night, 1)]);
+    assert_eq!(staff.var_name(&(Shift::Night, 1)), "staff_night_1");
+
+    let ref open = LpVarDict::binary("open", vec![Shift::Day, Shift::Night]);
+    assert_eq!(open[&Shift::Day].name, "open_day");
+}
+
+#[test]
+fn test_semi_continuous() {
+    let ref production = LpSemiContinuous::new("production").lower_bound(50.0).upper_bound(200.0);
+    let ref batches = LpSemiInteger::new("batches").lower_bound(2.0).upper_bound(10.0);
+
+    let mut problem = LpProblem::new("Semi", LpObjective::Minimize);
+    problem += production + batches;
+    problem += (production + 10 * batches).ge(30);
+
+    let output = problem.to_lp_file_format();
+    assert!(output.contains("50 <= production <= 200"));
+    assert!(output.contains("2 <= batches <= 10"));
+    assert!(output.contains("\nGenerals\n  batches \n"));
+    assert!(output.contains("\nSemi-continuous\n"));
+    let semis = output.split("Semi-continuous\n").nth(1).unwrap();
+    assert!(semis.contains("production") && semis.contains("batches"));
+
+    let reformulated = problem.reformulate_semi_continuous().unwrap();
+    assert!(!reformulated.has_semi_continuous());
+    let output = reformulated.to_lp_file_format();
+    assert!(!output.contains("Semi-continuous"));
+    assert!(output.contains("production - 200 production_on <= 0"));
+    assert!(output.contains("production - 50 production_on >= 0"));
+    assert!(output.contains("batches - 10 batches_on <= 0"));
+}
+
+#[test]
+fn test_special_ordered_sets() {
+    let ref a = LpContinuous::new("a").upper_bound(10.0);
+    let ref b = LpContinuous::new("b").upper_bound(10.0);
+    let ref c = LpContinuous::new("c").upper_bound(10.0);
+
+    let mut problem = LpProblem::new("Sos", LpObjective::Maximize);
+    problem += a + b + c;
+    problem += (a + b + c).le(15);
+    problem += LpSos::new("ab", SosType::Sos1, vec![(a, 1.0), (b, 2.0)]);
+    problem += LpSos::new("abc", SosType::Sos2, vec![(a, 1.0), (b, 2.5), (c, 4.0)]);
+
+    let output = problem.to_lp_file_format();
+    assert!(output.ends_with("\nSOS\n  ab: S1:: a:1 b:2\n  abc: S2:: a:1 b:2.5 c:4\n\nEnd\n"));
+}
+
+#[test]
+fn test_indicator_constraints() {
+    let ref x = LpContinuous::new("x").lower_bound(0.0).upper_bound(100.0);
+    let ref y = LpContinuous::new("y").lower_bound(-5.0).upper_bound(50.0);
+    let ref open = LpBinary::new("open");
+
+    let mut problem = LpProblem::new("Indicators", LpObjective::Maximize);
+    problem += x + y - 10 * open;
+    problem += (x + y).le(120);
+    problem += LpIndicator::new(open, false, x.le(0));
+    problem += LpIndicator::new(open, true, (x + 2 * y).ge(10).with_name("min_output"));
+
+    let output = problem.to_lp_file_format();
+    assert!(output.contains("  ind1: open = 0 -> x <= 0\n"));
+    assert!(output.contains("  min_output: open = 1 -> x + 2 y >= 10\n"));
+
+    let reformulated = problem.reformulate_indicators().unwrap();
+    assert!(reformulated.indicators.is_empty());
+    let output = reformulated.to_lp_file_format();
+    assert!(output.contains("  c2: x - 100 open <= 0\n"));
+    // x + 2 y - 10 >= -20 (1 - open)
+    assert!(output.contains("  min_output: x + 2 y - 20 open >= -10\n"));
+
+    let ref free = LpContinuous::new("free");
+    let unbounded = LpIndicator::new(open, true, free.le(3));
+    assert!(unbounded.to_big_m().is_err());
+}
+
+#[test]
+fn test_piecewise_linear() {
+    let breakpoints = vec![(0.0, 0.0), (10.0, 20.0), (20.0, 30.0)];
+    let ref x = LpContinuous::new("x");
+
+    let mut problem = LpProblem::new("Piecewise", LpObjective::Maximize);
+    let f = LpPiecewise::new("f", breakpoints.clone()).apply(&mut problem, x).unwrap();
+    problem += f;
+    let output = problem.to_lp_file_format();
+    assert!(output.contains("  f_convexity: f_lambda_0 + f_lambda_1 + f_lambda_2 = 1\n"));
+    assert!(output.contains("  f_sos: S2:: f_lambda_0:1 f_lambda_1:2 f_lambda_2:3\n"));
+
+    let mut problem = LpProblem::new("Piecewise", LpObjective::Maximize);
+    let f = LpPiecewise::new("f", breakpoints.clone())
+        .with_formulation(PiecewiseFormulation::Incremental)
+        .apply(&mut problem, x)
+        .unwrap();
+    problem += f;
+    assert!(problem.sos.is_empty());
+    let output = problem.to_lp_file_format();
+    assert!(output.contains("  f_order_0: f_z_0 - f_delta_0 <= 0\n"));
+    assert!(output.contains("\nBinary\n  f_z_0 "));
+
+    // concave function: the segments bound the output from above
+    let mut problem = LpProblem::new("Piecewise", LpObjective::Maximize);
+    let f = LpPiecewise::new("f", breakpoints.clone())
+        .with_formulation(PiecewiseFormulation::Convex)
+        .apply(&mut problem, x)
+        .unwrap();
+    problem += f;
+    let output = problem.to_lp_file_format();
+    assert!(output.contains("  f_segment_1: f_output - x <= 10\n"));
+    assert!(!output.contains("Binary"));
+
+    let mut problem = LpProblem::new("Piecewise", LpObjective::Maximize);
+    let not_convex = vec![(0.0, 0.0), (1.0, 1.0), (2.0, 1.0), (3.0, 2.0)];
+    assert!(LpPiecewise::new("g", not_convex)
+        .with_formulation(PiecewiseFormulation::Convex)
+        .apply(&mut problem, x)
+        .is_err());
+    assert!(LpPiecewise::new("h", vec![(1.0, 0.0), (1.0, 1.0)]).apply(&mut problem, x).is_err());
+}
+
+#[test]
+fn test_linearization() {
+    let ref x = LpContinuous::new("x").lower_bound(-2.0).upper_bound(5.0);
+    let ref y = LpContinuous::new("y").lower_bound(0.0).upper_bound(3.0);
+    let ref a = LpBinary::new("a");
+
+    let mut problem = LpProblem::new("Linearization", LpObjective::Minimize);
+    let abs = lp_abs(&mut problem, "abs", x).unwrap();
+    let max = lp_max(&mut problem, "max", &[x + 0, y + 1]).unwrap();
+    let product = lp_product(&mut problem, "product", a, y).unwrap();
+    problem += abs + max + product;
+    let output = problem.to_lp_file_format();
+    assert!(output.contains("  abs_split: x - abs_pos + abs_neg = 0\n"));
+    assert!(output.contains("  abs_neg_max: abs_neg + 2 abs_sign <= 2\n"));
+    assert!(output.contains("  max_above_1: max - y >= 1\n"));
+    assert!(output.contains("  max_select: max_select_0 + max_select_1 = 1\n"));
+    assert!(output.contains("  1 <= max <= 5\n"));
+    assert!(output.contains("  product_copy_ge: product - y - 3 a >= -3\n"));
+
+    // no auxiliary variable for an expression of constant sign
+    assert_eq!(lp_abs(&mut problem, "abs_y", y).unwrap(), y.into());
+    let ref free = LpContinuous::new("free");
+    assert!(lp_abs(&mut problem, "abs_free", free).is_err());
+    assert!(lp_max::<LpExpression>(&mut problem, "empty", &[]).is_err());
+}
+
+#[test]
+fn test_logical_constraints() {
+    let ref a = LpBinary::new("a");
+    let ref b = LpBinary::new("b");
+    let ref c = LpBinary::new("c");
+    let all = vec![a.clone(), b.clone(), c.clone()];
+
+    let mut problem = LpProblem::new("Logic", LpObjective::Maximize);
+    problem += a + b + c;
+    problem += a.implies(b).with_name("a_needs_b");
+    problem += b.excludes(c).with_name("b_or_c");
+    problem += at_most_k(&all, 2).with_name("two");
+    let ref both = lp_and(&mut problem, "both", &[a.clone(), b.clone()]);
+    let ref either = lp_or(&mut problem, "either", &[a.clone(), c.clone()]);
+    problem += both.equivalent(either);
+    let output = problem.to_lp_file_format();
+    assert!(output.contains("  a_needs_b: a - b <= 0\n"));
+    assert!(output.contains("  b_or_c: b + c <= 1\n"));
+    assert!(output.contains("  two: a + b + c <= 2\n"));
+    assert!(output.contains("  both_1: both - b <= 0\n"));
+    assert!(output.contains("  both_all: both - a - b >= -1\n"));
+    assert!(output.contains("  either_any: either - a - c <= 0\n"));
+    assert!(output.contains("both - either = 0\n"));
+}
+
+#[test]
+fn test_variable_registry() {
+    let ref a = LpContinuous::new("a").lower_bound(0.0);
+    let ref penalty = LpInteger::new("penalty").lower_bound(0.0).upper_bound(3.0);
+    let ref open = LpBinary::new("open");
+    let ref extra = LpContinuous::new("extra");
+
+    let mut problem = LpProblem::new("Registry", LpObjective::Minimize);
+    problem += a + 10 * penalty + open;
+    problem += LpMultiObjective::new("extra", extra).with_priority(-1);
+    problem += a.ge(1);
+
+    let variables = problem.variables();
+    let names: Vec<&str> = variables.names().collect();
+    assert_eq!(names, vec!["a", "penalty", "open", "extra"]);
+    assert_eq!(variables.get("penalty").unwrap().bounds(), (Some(0.0), Some(3.0)));
+    assert!(variables.get("open").unwrap().is_integer());
+    assert!(!variables.contains("b"));
+
+    let output = problem.to_lp_file_format();
+    assert!(output.contains("  0 <= penalty <= 3\n"));
+    assert!(output.contains("  extra free\n"));
+    assert!(output.contains("\nGenerals\n  penalty \n"));
+    assert!(output.contains("\nBinary\n  open \n"));
+}
+
+#[test]
+fn test_problem_mutation() {
+    let ref a = LpInteger::new("a").lower_bound(0.0);
+    let ref b = LpContinuous::new("b");
+    let ref open = LpBinary::new("open");
+
+    let mut problem = LpProblem::new("What if", LpObjective::Maximize);
+    problem += 2 * a + b;
+    problem += (a + b).le(10);
+    problem += (a - b).ge(2).with_name("gap");
+    problem += b.le(5);
+    problem += (a - 10 * open).le(0);
+
+    // the unnamed constraints after a removed one keep their identifiers
+    assert_eq!(problem.remove_constraint("c1"), Some((a + b).le(10)));
+    assert_eq!(problem.constraint_id(1), Some("c3".to_string()));
+    assert!(problem.remove_constraint("c1").is_none());
+
+    assert!(problem.replace_constraint("c3", b.le(4)).is_some());
+    problem.set_rhs("gap", 3.0).unwrap();
+    assert!(problem.set_range("gap", 0.0, 1.0).is_err());
+    assert!(problem.set_rhs("unknown", 3.0).is_err());
+
+    problem.set_objective_type(LpObjective::Minimize);
+    problem.set_objective_coefficient(b, -1.0).unwrap();
+    problem.set_objective_coefficient(open, 5.0).unwrap();
+
+    problem.set_bounds("b", Some(-1.0), None).unwrap();
+    problem.fix_variable("a", 4.0).unwrap();
+    problem.fix_variable("open", 1.0).unwrap();
+    assert!(problem.set_bounds("open", None, None).is_err());
+    assert!(problem.set_bounds("c", None, None).is_err());
+
+    let output = problem.to_lp_file_format();
+    assert!(output.contains("Minimize\n  obj: 2 a - b + 5 open\n"));
+    assert!(output.contains("  gap: a - b >= 3\n"));
+    assert!(output.contains("  c3: b <= 4\n"));
+    assert!(output.contains("  open_fixed: open = 1\n"));
+    assert!(output.contains("  4 <= a <= 4\n"));
+    assert!(output.contains("  -1 <= b\n"));
+
+    problem += (a + b).between(1, 3).with_name("range");
+    problem.set_range("range", 2.0, 6.0).unwrap();
+    assert!(problem.set_rhs("range", 1.0).is_err());
+    assert!(problem.set_range("range", 6.0, 2.0).is_err());
+    assert!(problem.to_lp_file_format().contains("  range: 2 <= a + b <= 6\n"));
+}
+
+#[test]
+fn test_remove_then_add_constraint() {
+    let ref a = LpInteger::new("a");
+
+    let mut problem = LpProblem::new("Identifiers", LpObjective::Maximize);
+    problem += a;
+    problem += a.le(1);
+    problem += a.le(2);
+    problem += a.le(3);
+    assert!(problem.remove_constraint("c2").is_some());
+    assert_eq!(problem.add_constraint(a.ge(1)), "c4");
+    assert!(problem.remove_constraint("c1").is_some());
+    problem += a.ge(0);
+
+    let ids: Vec<String> = (0..problem.constraints.len()).filter_map(|index| problem.constraint_id(index)).collect();
+    assert_eq!(ids, vec!["c3", "c4", "c5"]);
+    assert_eq!(problem.constraint("c3"), Some(&a.le(3).with_name("c3")));
+    assert_eq!(problem.constraint("c5"), Some(&a.ge(0)));
+    assert!(problem.validate().check().is_ok());
+    let output = problem.to_lp_file_format();
+    assert!(output.contains("  c3: a <= 3\n  c4: a >= 1\n  c5: a >= 0\n"));
+}
+
+#[test]
+fn test_add_column() {
+    let ref a = LpContinuous::new("a").lower_bound(0.0);
+    let ref b = LpInteger::new("b").lower_bound(0.0).upper_bound(4.0);
+
+    let mut problem = LpProblem::new("Columns", LpObjective::Minimize);
+    problem += 3 * a;
+    let demand = problem.add_constraint(a.ge(10));
+    problem += a.le(20).with_name("capacity");
+    assert_eq!(demand, "c1");
+
+    let column = LpColumn::new(b).with_objective(2.0).with_coefficient("c1", 3.0).with_coefficient("capacity", 1.0);
+    problem.add_column(column).unwrap();
+    assert!(problem.add_column(LpColumn::new(b).with_coefficient("unknown", 1.0)).is_err());
+    assert!(problem.add_column(LpColumn::new(a + b)).is_err());
+
+    let output = problem.to_lp_file_format();
+    assert!(output.contains("Minimize\n  obj: 3 a + 2 b\n"));
+    assert!(output.contains("  c1: a + 3 b >= 10\n"));
+    assert!(output.contains("  capacity: a + b <= 20\n"));
+    assert!(output.contains("  0 <= b <= 4\n"));
+    assert!(output.contains("\nGenerals\n  b \n"));
+}
+
+#[test]
+fn test_multi_objectives() {
+    let ref x = LpContinuous::new("x");
+    let ref y = LpContinuous::new("y");
+
+    let mut problem = LpProblem::new("Multi", LpObjective::Minimize);
+    problem += LpMultiObjective::new("cost", 2 * x + y).with_priority(2).with_rel_tol(0.1);
+    problem += LpMultiObjective::new("delay", y).with_priority(1).with_weight(3.0).with_abs_tol(1.5);
+    problem += LpMultiObjective::new("risk", x).with_priority(1);
+    problem += (x + y).ge(3);
+    assert!(problem.has_multi_objectives());
+
+    let output = problem.to_lp_file_format();
+    assert!(output.contains("Minimize multi-objectives\n  \
+        cost: Priority=2 Weight=1 AbsTol=0 RelTol=0.1\n    2 x + y\n  \
+        delay: Priority=1 Weight=3 AbsTol=1.5 RelTol=0\n    y\n  \
+        risk: Priority=1 Weight=1 AbsTol=0 RelTol=0\n    x\n\n\
+        Subject To\n"));
+
+    // an objective set with `+=` is the first one
+    problem += x - y;
+    assert_eq!(problem.multi_objectives()[0], LpMultiObjective::new("obj", x - y));
+    assert!(problem.to_lp_file_format().contains("  obj: Priority=0 Weight=1 AbsTol=0 RelTol=0\n    x - y\n"));
+}
+
+#[test]
+fn test_quadratic_objective() {
+    let ref x = LpContinuous::new("x").lower_bound(0.0);
+    let ref y = LpContinuous::new("y").lower_bound(0.0);
+
+    let mut problem = LpProblem::new("Portfolio", LpObjective::Minimize);
+    problem += 2 * x * x + x * y + y * x + 3 * y * y - x;
+    problem += (x + y).equal(1);
+    assert!(problem.has_quadratic_objective());
+    assert!(!problem.has_quadratic_constraints());
+
+    let output = problem.to_lp_file_format();
+    assert!(output.contains("  obj: -x + [ 4 x ^ 2 + 4 x * y + 6 y ^ 2 ] / 2\n"));
+
+    problem += (x * y).le(0.2).with_name("product");
+    assert!(problem.has_quadratic_constraints());
+    assert!(problem.to_lp_file_format().contains("  product: [ x * y ] <= 0.2\n"));
+    assert!(CbcSolver::new().run(&problem).is_err());
+
+    let mut objective_only = LpProblem::new("Portfolio", LpObjective::Minimize);
+    objective_only += x * y;
+    let error = CbcSolver::new().run(&objective_only).unwrap_err();
+    assert_eq!(error, "Quadratic objectives and constraints are not supported by the Cbc solver");
+
+    let mut cubic = LpProblem::new("Cubic", LpObjective::Minimize);
+    cubic += x * x * y;
+    let error = cubic.write_lp("cubic.lp").unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(error.to_string().starts_with("Expression of degree higher than two"));
+    assert!(!std::path::Path::new("cubic.lp").exists());
+
+    // products cancelling out are linear
+    assert!((x * y - y * x + x).is_linear());
+    assert!(!((x + 1) * (y - 2)).is_linear());
+}
+
+#[test]
+fn test_range_constraints() {
+    let ref x = LpContinuous::new("x").lower_bound(0.0).upper_bound(10.0);
+    let ref y = LpContinuous::new("y").lower_bound(0.0).upper_bound(10.0);
+    let ref open = LpBinary::new("open");
+
+    let mut problem = LpProblem::new("Ranges", LpObjective::Maximize);
+    problem += x + y;
+    problem += (x + 2 * y).between(4, 12).with_name("capacity");
+    problem += (x - y).between(-2, 2);
+    problem += LpIndicator::new(open, true, (x + y).between(1, 5).with_name("when_open"));
+    assert_eq!(problem.constraints.len(), 2);
+    assert_eq!(problem.constraints[0].1, Constraint::Range(8.0));
+
+    let output = problem.to_lp_file_format();
+    assert!(output.contains("  capacity: 4 <= x + 2 y <= 12\n"));
+    assert!(output.contains("  c2: -2 <= x - y <= 2\n"));
+
+    let split = problem.reformulate_ranges();
+    assert!(!split.has_ranges());
+    let output = split.to_lp_file_format();
+    assert!(output.contains("  capacity_le: x + 2 y <= 12\n"));
+    assert!(output.contains("  capacity_ge: x + 2 y >= 4\n"));
+    assert!(output.contains("  c2_ge: x - y >= -2\n"));
+
+    // x + y - 5 <= 15 (1 - open) and x + y - 1 >= -(1 - open)
+    let output = problem.reformulate_indicators().unwrap().to_lp_file_format();
+    assert!(output.contains("  when_open_le: x + y + 15 open <= 20\n"));
+    assert!(output.contains("  when_open_ge: x + y - open >= 0\n"));
+
+    problem += (x + y).between(5, 3).with_name("empty");
+    assert_eq!(problem.validate().errors, vec![LpValidationIssue::EmptyRange { constraint: "empty".to_string(), width: -2.0 }]);
+}
+
+#[test]
+fn test_validation() {
+    let ref x = LpContinuous::new("x").lower_bound(0.0).upper_bound(10.0);
+    let ref y = LpContinuous::new("y").lower_bound(0.0);
+
+    let mut problem = LpProblem::new("Valid", LpObjective::Maximize);
+    problem += x + y;
+    problem += (x + y).le(10).with_name("capacity");
+    assert_eq!(problem.validate(), LpValidation::default());
+
+    let ref x_integer = LpInteger::new("x");
+    let ref y_bounded = LpContinuous::new("y").lower_bound(0.0).upper_bound(5.0);
+    let ref z = LpContinuous::new("z").lower_bound(3.0).upper_bound(1.0);
+    problem += (x_integer + y_bounded).ge(1);
+    problem += (z + f64::NAN * x).le(4).with_name("capacity");
+    problem += (x - x).le(-3);
+    problem += (y - y).ge(-3);
+
+    let validation = problem.validate();
+    assert_eq!(validation.errors, vec![
+        LpValidationIssue::ConflictingTypes { name: "x".to_string() },
+        LpValidationIssue::ConflictingBounds { name: "y".to_string() },
+        LpValidationIssue::EmptyDomain { name: "z".to_string(), lower_bound: 3.0, upper_bound: 1.0 },
+        LpValidationIssue::InvalidNumber { location: "constraint capacity".to_string() },
+        LpValidationIssue::InfeasibleConstraint { constraint: "c4".to_string() },
+        LpValidationIssue::DuplicateConstraintName { name: "capacity".to_string() },
+    ]);
+    assert_eq!(validation.warnings, vec![LpValidationIssue::TrivialConstraint { constraint: "c5".to_string() }]);
+
+    let message = validation.check().unwrap_err();
+    assert!(message.contains("variable x is declared with different types"));
+    // the validation runs before looking for the solver binary
+    let error = CbcSolver::new().with_validation(true).run(&problem).unwrap_err();
+    assert_eq!(error, message);
+}
+
+#[test]
+fn test_feasibility_check() {
+    let ref x = LpInteger::new("x").lower_bound(0.0).upper_bound(4.0);
+    let ref y = LpContinuous::new("y").lower_bound(0.0);
+    let ref s = LpSemiContinuous::new("s").lower_bound(2.0).upper_bound(5.0);
+    let ref open = LpBinary::new("open");
+    let ref a = LpContinuous::new("a");
+    let ref b = LpContinuous::new("b");
+    let ref c = LpContinuous::new("c");
+
+    let mut problem = LpProblem::new("Check", LpObjective::Maximize);
+    problem += x + y + s;
+    problem += (x + y).le(6).with_name("capacity");
+    problem += (x - y).equal(1);
+    problem.add_indicator(LpIndicator::new(open, true, y.ge(3)));
+    problem.add_sos(LpSos::new("pick", SosType::Sos1, vec![(a, 1.0), (b, 2.0), (c, 3.0)]));
+
+    let values = |pairs: &[(&str, f64)]| -> HashMap<String, f64> {
+        pairs.iter().map(|&(name, value)| (name.to_string(), value)).collect()
+    };
+
+    let check = FeasibilityCheck::new();
+    let report = check.check(&problem, &values(&[("x", 3.0), ("y", 2.0), ("b", 1.0)])).unwrap();
+    assert!(report.is_feasible());
+    assert_eq!(report.max_violation(), 0.0);
+    assert_eq!(report.check(), Ok(()));
+
+    let report = check.check(&problem, &values(&[
+        ("x", 4.5), ("y", 2.5), ("s", 1.0), ("open", 1.0), ("a", 1.0), ("b", -2.0), ("c", 0.5),
+    ])).unwrap();
+    assert_eq!(report.violations, vec![
+        LpViolation::Constraint { constraint: "capacity".to_string(), activity: 7.0, magnitude: 1.0 },
+        LpViolation::Constraint { constraint: "c2".to_string(), activity: 2.0, magnitude: 1.0 },
+        LpViolation::Indicator { constraint: "ind1".to_string(), activity: 2.5, magnitude: 0.5 },
+        LpViolation::SpecialOrderedSet { set: "pick".to_string(), magnitude: 1.5 },
+        LpViolation::Bound { name: "x".to_string(), value: 4.5, magnitude: 0.5 },
+        LpViolation::Integrality { name: "x".to_string(), value: 4.5, magnitude: 0.5 },
+        LpViolation::SemiContinuous { name: "s".to_string(), value: 1.0, magnitude: 1.0 },
+    ]);
+    assert_eq!(report.max_violation(), 1.5);
+    assert!(report.check().unwrap_err().contains("constraint capacity is violated by 1"));
+
+    // inactive indicator and values within the tolerances
+    let report = check
+        .with_feasibility_tol(0.1)
+        .with_integrality_tol(0.1)
+        .check(&problem, &values(&[("x", 3.05), ("y", 2.0), ("open", 0.0)]))
+        .unwrap();
+    assert!(report.is_feasible());
+}
+
+#[test]
+fn test_constraint_activity() {
+    let ref a = LpContinuous::new("a");
+    let ref b = LpContinuous::new("b");
+
+    let mut problem = LpProblem::new("Activity", LpObjective::Maximize);
+    problem += 3 * a + b;
+    problem += (a + b).le(4).with_name("machine");
+    problem += (a - b).ge(1).with_name("balance");
+    problem += (a + 2 * b).equal(4);
+    problem += a.between(1, 3).with_name("orders");
+    problem += b.le(0.5).with_name("violated");
+
+    let results: HashMap<String, f64> = vec![("a".to_string(), 2.0), ("b".to_string(), 1.0)].into_iter().collect();
+    let solution = Solution::with_problem(Status::Optimal, results, &problem);
+    let activities: Vec<(String, f64, Option<f64>, Option<f64>, f64, bool)> = solution.constraint_activities().unwrap().into_iter()
+        .map(|activity| (activity.id.clone(), activity.activity, activity.lower, activity.upper, activity.slack, activity.is_binding()))
+        .collect();
+    assert_eq!(activities, vec![
+        ("machine".to_string(), 3.0, None, Some(4.0), 1.0, false),
+        ("balance".to_string(), 1.0, Some(1.0), None, 0.0, true),
+        ("c3".to_string(), 4.0, Some(4.0), Some(4.0), 0.0, true),
+        ("orders".to_string(), 2.0, Some(1.0), Some(3.0), 1.0, false),
+        ("violated".to_string(), 1.0, None, Some(0.5), -0.5, false),
+    ]);
+    assert_eq!(solution.constraint_activity("c3").unwrap().activity, 4.0);
+    assert!(solution.constraint_activity("unknown").is_err());
+    assert_eq!(solution.eval_expr(&(3 * a + b - 2)), Ok(5.0));
+
+    let unrelated = Solution::new(Status::Optimal, HashMap::new());
+    assert!(unrelated.constraint_activities().is_err());
+}
+
+/// Solver of `cost + emissions = 10` with both variables in [2, 8], recording the objective
+/// and the epsilon bound of each problem it solves
+struct TradeOffSolver {
+    solved: RefCell<Vec<(String, Option<f64>)>>,
+}
+
+impl SolverTrait for TradeOffSolver {
+    type P = LpProblem;
+    fn run<'a>(&self, problem: &'a LpProblem) -> Result<Solution<'a>, String> {
+        let objective = problem.obj_expr_arena.as_ref().unwrap().to_lp_file_format();
+        let epsilon = problem.constraint("epsilon_0").map(|bound| bound.2.to_linear().unwrap().constant());
+        self.solved.borrow_mut().push((objective.clone(), epsilon));
+        let cost = match (objective.as_str(), epsilon) {
+            ("cost", Some(epsilon)) => (10.0 - epsilon).max(2.0),
+            ("cost", None) => 2.0,
+            _ => 8.0,
+        };
+        let results = vec![("cost".to_string(), cost), ("emissions".to_string(), 10.0 - cost)].into_iter().collect();
+        Ok(Solution::with_problem(Status::Optimal, results, problem))
+    }
+}
+
+#[test]
+fn test_pareto_front() {
+    let ref cost = LpContinuous::new("cost").lower_bound(2.0).upper_bound(8.0);
+    let ref emissions = LpContinuous::new("emissions").lower_bound(2.0).upper_bound(8.0);
+    let mut problem = LpProblem::new("Plan", LpObjective::Minimize);
+    problem += (cost + emissions).equal(10);
+
+    let solver = TradeOffSolver { solved: RefCell::new(Vec::new()) };
+    let front = EpsilonConstraint::new(cost).with_objective(emissions, 3).run(&solver, &problem).unwrap();
+
+    // payoff table, then the primary objective with the bound stepped from the worst value
+    let solved = |objective: &str, epsilon: Option<f64>| (objective.to_string(), epsilon);
+    assert_eq!(*solver.solved.borrow(), vec![
+        solved("cost", None),
+        solved("emissions", None),
+        solved("cost", Some(8.0)),
+        solved("cost", Some(6.0)),
+        solved("cost", Some(4.0)),
+        solved("cost", Some(2.0)),
+    ]);
+    let objectives: Vec<Vec<f64>> = front.iter().map(|point| point.objectives.clone()).collect();
+    assert_eq!(objectives, vec![vec![2.0, 8.0], vec![4.0, 6.0], vec![6.0, 4.0], vec![8.0, 2.0]]);
+    assert_eq!(front[1].solution.results["emissions"], 6.0);
+}
+
 #[test]
 fn test_readme_example_2() {
     // Problem Data
     let men = vec!["A", "B", "C"];
     let women = vec!["D", "E", "F"];
-    let compat_scores : HashMap<(&str,&str),f32> = vec![
+    let compat_scores : HashMap<(&str,&str),f64> = vec![
         (("A", "D"), 50.0),
         (("A", "E"), 75.0),
         (("A", "F"), 75.0),
@@ -152,10 +784,10 @@ fn test_readme_example_2() {
 
     // Terminate if error, or assign status & variable values
     assert!(result.is_ok(), result.unwrap_err());
-    let Solution { status: solver_status, results: var_values, related_problem: _ } = result.unwrap();
+    let Solution { status: solver_status, results: var_values, .. } = result.unwrap();
 
     // Compute final objective function value
-    let mut obj_value = 0f32;
+    let mut obj_value = 0f64;
     for (&(&m, &w), var) in &vars {
         let obj_coef = compat_scores.get(&(m, w)).unwrap();
         let var_value = var_values.get(&var.name).unwrap();
@@ -175,10 +807,10 @@ fn test_readme_example_2() {
     }
 
     assert_eq!(solver_status, lp_modeler::solvers::Status::Optimal);
-    assert_eq!(obj_value, 230f32);
-    assert_eq!(*var_values.get("A_F").unwrap(), 1f32);
-    assert_eq!(*var_values.get("B_E").unwrap(), 1f32);
-    assert_eq!(*var_values.get("C_D").unwrap(), 1f32);
+    assert_eq!(obj_value, 230f64);
+    assert_eq!(*var_values.get("A_F").unwrap(), 1f64);
+    assert_eq!(*var_values.get("B_E").unwrap(), 1f64);
+    assert_eq!(*var_values.get("C_D").unwrap(), 1f64);
 }
 
 #[cfg(feature = "native_coin_cbc")]
@@ -189,7 +821,7 @@ fn test_readme_example_2() {
 // s.t.      2a - 8b + 4c + 2d + 5e <= 10
 fn cbc_native_optimal() {
     let mut problem = LpProblem::new("Knapsack", LpObjective::Maximize);
-    let objective: HashMap<&str, f32> =
+    let objective: HashMap<&str, f64> =
         vec![("a", 5.0), ("b", 3.0), ("c", 2.0), ("d", 7.0), ("e", 4.0)]
             .into_iter()
             .collect();
@@ -208,7 +840,7 @@ fn cbc_native_optimal() {
             println!("Status {:?}", sol.status);
             println!("{:?}", sol.results);
             assert_eq!(
-                17f32,
+                17f64,
                 x.iter()
                     .map(|(name, var)| match sol.results.get(&var.name) {
                         Some(s) => {
@@ -0,0 +1,5 @@
+Optimal - objective value 24.00000000
+      0 capacity               6                      4
+      1 c2                     2                      0
+      0 a                      6                      0
+      1 b                      0                     -1
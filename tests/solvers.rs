@@ -1,5 +1,8 @@
 extern crate lp_modeler;
+#[cfg(feature = "serde")]
+extern crate serde_json;
 
+use lp_modeler::dsl::*;
 use lp_modeler::solvers::*;
 use std::fs;
 
@@ -7,11 +10,24 @@ use std::fs;
 fn cbc_optimal() {
     let _ = fs::copy("tests/solution_files/cbc_optimal.sol", "cbc_optimal.sol");
     let solver = CbcSolver::new().with_temp_solution_file("cbc_optimal.sol".to_string());
-    let Solution { status, results: mut variables, related_problem: _ } = solver.read_solution(&"cbc_optimal.sol".to_string(), None).unwrap();
+    let Solution { status, results: mut variables, .. } = solver.read_solution(&"cbc_optimal.sol".to_string(), None).unwrap();
     assert_eq!(status, Status::Optimal);
-    assert_eq!(variables.remove("a"), Some(5f32));
-    assert_eq!(variables.remove("b"), Some(6f32));
-    assert_eq!(variables.remove("c"), Some(0f32));
+    assert_eq!(variables.remove("a"), Some(5f64));
+    assert_eq!(variables.remove("b"), Some(6f64));
+    assert_eq!(variables.remove("c"), Some(0f64));
+}
+
+#[test]
+fn cbc_duals() {
+    let _ = fs::copy("tests/solution_files/cbc_optimal_duals.sol", "cbc_optimal_duals.sol");
+    let solver = CbcSolver::new();
+    let solution = solver.read_solution(&"cbc_optimal_duals.sol".to_string(), None).unwrap();
+    assert_eq!(solution.results, vec![("a".to_string(), 6.0), ("b".to_string(), 0.0)].into_iter().collect());
+    assert_eq!(solution.dual("capacity"), Some(4.0));
+    assert_eq!(solution.dual("c2"), Some(0.0));
+    assert_eq!(solution.dual("a"), None);
+    assert_eq!(solution.reduced_cost("b"), Some(-1.0));
+    assert_eq!(solution.reduced_cost("capacity"), None);
 }
 
 #[test]
@@ -21,7 +37,7 @@ fn cbc_infeasible() {
         "cbc_infeasible.sol",
     );
     let solver = CbcSolver::new().with_temp_solution_file("cbc_infeasible.sol".to_string());
-    let Solution { status, results: _, related_problem: _ } = solver.read_solution(&"cbc_infeasible.sol".to_string(), None).unwrap();
+    let Solution { status, results: _, .. } = solver.read_solution(&"cbc_infeasible.sol".to_string(), None).unwrap();
     assert_eq!(status, Status::Infeasible);
 }
 
@@ -40,13 +56,13 @@ fn cbc_infeasible_alternative_format() {
         "tests/solution_files/cbc_infeasible_alternative_format.sol",
         "cbc_infeasible_alternative_format.sol",
     );
-    let Solution { status, results: mut variables, related_problem: _ } = CbcSolver::new()
+    let Solution { status, results: mut variables, .. } = CbcSolver::new()
         .with_temp_solution_file("cbc_infeasible_alternative_format.sol".to_string())
         .read_solution(&"cbc_infeasible_alternative_format.sol".to_string(), None)
         .unwrap();
     assert_eq!(status, Status::Infeasible);
-    assert_eq!(variables.remove("a"), Some(2f32));
-    assert_eq!(variables.remove("b"), Some(0f32));
+    assert_eq!(variables.remove("a"), Some(2f64));
+    assert_eq!(variables.remove("b"), Some(0f64));
 }
 
 #[test]
@@ -56,7 +72,7 @@ fn cbc_unbounded() {
         "cbc_unbounded.sol",
     );
     let solver = CbcSolver::new().with_temp_solution_file("cbc_unbounded.sol".to_string());
-    let Solution { status, results: _, related_problem: _ } = solver.read_solution(&"cbc_unbounded.sol".to_string(), None).unwrap();
+    let Solution { status, results: _, .. } = solver.read_solution(&"cbc_unbounded.sol".to_string(), None).unwrap();
     assert_eq!(status, Status::Unbounded);
 }
 
@@ -64,11 +80,11 @@ fn cbc_unbounded() {
 fn glpk_optimal() {
     let _ = fs::copy("tests/solution_files/glpk_optimal.sol", "glpk_optimal.sol");
     let solver = GlpkSolver::new().with_temp_solution_file("glpk_optimal.sol".to_string());
-    let Solution { status, results: mut variables, related_problem: _ } = solver.read_solution(&"glpk_optimal.sol".to_string(), None).unwrap();
+    let Solution { status, results: mut variables, .. } = solver.read_solution(&"glpk_optimal.sol".to_string(), None).unwrap();
     assert_eq!(status, Status::Optimal);
-    assert_eq!(variables.remove("a"), Some(0f32));
-    assert_eq!(variables.remove("b"), Some(5f32));
-    assert_eq!(variables.remove("c"), Some(0f32));
+    assert_eq!(variables.remove("a"), Some(0f64));
+    assert_eq!(variables.remove("b"), Some(5f64));
+    assert_eq!(variables.remove("c"), Some(0f64));
 }
 
 #[test]
@@ -78,7 +94,7 @@ fn glpk_infeasible() {
         "glpk_infeasible.sol",
     );
     let solver = GlpkSolver::new().with_temp_solution_file("glpk_infeasible.sol".to_string());
-    let Solution { status, results: _, related_problem: _ } = solver.read_solution(&"glpk_infeasible.sol".to_string(), None).unwrap();
+    let Solution { status, results: _, .. } = solver.read_solution(&"glpk_infeasible.sol".to_string(), None).unwrap();
     assert_eq!(status, Status::Infeasible);
 }
 
@@ -89,7 +105,7 @@ fn glpk_unbounded() {
         "glpk_unbounded.sol",
     );
     let solver = GlpkSolver::new().with_temp_solution_file("glpk_unbounded.sol".to_string());
-    let Solution { status, results: _, related_problem: _ } = solver.read_solution(&"glpk_unbounded.sol".to_string(), None).unwrap();
+    let Solution { status, results: _, .. } = solver.read_solution(&"glpk_unbounded.sol".to_string(), None).unwrap();
     assert_eq!(status, Status::Unbounded);
 }
 
@@ -100,8 +116,108 @@ fn glpk_empty_col_bounds() {
         "glpk_empty_col_bounds.sol",
     );
     let solver = GlpkSolver::new().with_temp_solution_file("glpk_empty_col_bounds.sol".to_string());
-    let Solution { status, results: solution, related_problem: _} = solver.read_solution(&"glpk_empty_col_bounds.sol".to_string(), None).unwrap();
+    let Solution { status, results: solution, .. } = solver.read_solution(&"glpk_empty_col_bounds.sol".to_string(), None).unwrap();
     assert_eq!(status, Status::Optimal);
     assert_eq!(1.0, *solution.get("a").unwrap());
     assert_eq!(0.0, *solution.get("b").unwrap());
 }
+
+#[test]
+fn glpk_duals() {
+    let _ = fs::copy(
+        "tests/solution_files/glpk_empty_col_bounds.sol",
+        "glpk_duals.sol",
+    );
+    let solver = GlpkSolver::new();
+    let solution = solver.read_solution(&"glpk_duals.sol".to_string(), None).unwrap();
+    assert_eq!(solution.dual("c1"), Some(0.0));
+    assert_eq!(solution.dual("c2"), Some(-1.0));
+    assert_eq!(solution.dual("c3"), Some(1.0));
+    assert_eq!(solution.reduced_cost("a"), Some(0.0));
+    assert_eq!(solution.reduced_cost("b"), Some(0.0));
+
+    // no marginals for integer problems
+    let _ = fs::copy("tests/solution_files/glpk_optimal.sol", "glpk_duals.sol");
+    let solution = solver.read_solution(&"glpk_duals.sol".to_string(), None).unwrap();
+    assert!(solution.duals.is_empty());
+    assert!(solution.reduced_costs.is_empty());
+}
+
+#[test]
+fn cbc_initial_solution() {
+    let ref a = LpInteger::new("a");
+    let ref b = LpBinary::new("b");
+    let mut problem = LpProblem::new("Start", LpObjective::Maximize);
+    problem += a + b;
+    problem += (a + b).le(3);
+
+    let previous = Solution::new(Status::Optimal, vec![("b".to_string(), 1.0), ("a".to_string(), 2.0), ("old".to_string(), 4.0)].into_iter().collect());
+    let solver = CbcSolver::new().with_initial_solution_from(&previous);
+    assert_eq!(solver.initial_solution(), Some(&previous.results));
+
+    // the start is written in the solution format of cbc, without the unknown variable
+    solver.write_initial_solution("cbc_start.mst", &problem).unwrap();
+    let Solution { results, .. } = solver.read_solution(&"cbc_start.mst".to_string(), None).unwrap();
+    assert_eq!(results, vec![("a".to_string(), 2.0), ("b".to_string(), 1.0)].into_iter().collect());
+
+    assert!(CbcSolver::new().write_initial_solution("cbc_no_start.mst", &problem).is_err());
+}
+
+#[test]
+fn gurobi_initial_solution() {
+    let ref a = LpInteger::new("a");
+    let ref b = LpBinary::new("b");
+    let mut problem = LpProblem::new("Start", LpObjective::Maximize);
+    problem += a + b;
+    problem += (a + b).le(3);
+
+    // partial assignment
+    let solver = GurobiSolver::new().with_initial_solution(vec![("b".to_string(), 1.0)].into_iter().collect());
+    solver.write_initial_solution("gurobi_start.mst", &problem).unwrap();
+    let content = fs::read_to_string("gurobi_start.mst").unwrap();
+    let _ = fs::remove_file("gurobi_start.mst");
+    assert_eq!(content, "# MIP start\nb 1\n");
+}
+
+#[test]
+fn gurobi_pool() {
+    let ref a = LpBinary::new("a");
+    let ref b = LpBinary::new("b");
+    let ref c = LpBinary::new("c");
+    let mut problem = LpProblem::new("Pool", LpObjective::Maximize);
+    problem += 5 * a + 4 * b + 3 * c;
+    problem += (a + b + c).le(2);
+
+    let _ = fs::copy("tests/solution_files/gurobi_pool.json", "gurobi_pool.json");
+    let solutions = GurobiSolver::new().read_pool("gurobi_pool.json", &problem).unwrap();
+    let values: Vec<(Status, Option<f64>, bool, bool, bool)> = solutions.iter()
+        .map(|solution| (solution.status.clone(), solution.eval(), solution.get_bool(a), solution.get_bool(b), solution.get_bool(c)))
+        .collect();
+    assert_eq!(values, vec![
+        (Status::Optimal, Some(9.0), true, true, false),
+        (Status::Optimal, Some(8.0), true, false, true),
+        (Status::Optimal, Some(7.0), false, true, true),
+    ]);
+
+    let _ = fs::copy("tests/solution_files/gurobi_pool_infeasible.json", "gurobi_pool_infeasible.json");
+    let solutions = GurobiSolver::new().read_pool("gurobi_pool_infeasible.json", &problem).unwrap();
+    assert_eq!(solutions.len(), 1);
+    assert_eq!(solutions[0].status, Status::Infeasible);
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn serialize_owned_solution() {
+    let ref a = LpInteger::new("a");
+    let mut problem = LpProblem::new("Serialized", LpObjective::Minimize);
+    problem += 2 * a;
+    problem += a.ge(2);
+
+    let results = vec![("a".to_string(), 2.0)].into_iter().collect();
+    let owned = Solution::with_problem(Status::Optimal, results, &problem).to_owned_solution();
+    let json = serde_json::to_string(&owned).unwrap();
+    let deserialized: OwnedSolution = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, owned);
+    assert_eq!(deserialized.eval(), Some(4.0));
+    assert_eq!(deserialized.variable_type("a"), Some(VariableType::Integer));
+}